@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+/// CPU time allowed for the passes of a frame, for skipping optional passes
+/// on slow frames, see `Renderer::render_pass_with_budget`.
+///
+/// Times are in nanoseconds.
+#[derive(Debug, Clone, Default)]
+pub struct FrameBudget {
+    pub total_ns: u64,
+    /// Passes whose last duration was above their limit are skipped.
+    pub per_pass_limits: HashMap<String, u64>,
+    spent_ns: u64,
+    last_pass_ns: HashMap<String, u64>,
+}
+
+impl FrameBudget {
+    pub fn new(total_ns: u64) -> Self {
+        Self {
+            total_ns,
+            ..Self::default()
+        }
+    }
+    /// Starts a new frame, call once per frame before the first pass.
+    pub fn reset(&mut self) {
+        self.spent_ns = 0;
+    }
+    /// The time recorded since the last `reset`.
+    pub fn spent_ns(&self) -> u64 {
+        self.spent_ns
+    }
+    /// Whether the pass `label` would exceed its limit, or the total budget
+    /// after `elapsed_ns`, if it takes as long as the last time it was
+    /// recorded.
+    ///
+    /// Passes that were never recorded are never skipped.
+    pub fn should_skip_pass(&self, label: &str, elapsed_ns: u64) -> bool {
+        let estimate_ns = match self.last_pass_ns.get(label) {
+            Some(&estimate_ns) => estimate_ns,
+            None => return false,
+        };
+        let over_limit = self
+            .per_pass_limits
+            .get(label)
+            .is_some_and(|&limit_ns| estimate_ns > limit_ns);
+        over_limit || elapsed_ns.saturating_add(estimate_ns) > self.total_ns
+    }
+    /// Records that the pass `label` took `duration_ns`.
+    pub fn record(&mut self, label: &str, duration_ns: u64) {
+        self.spent_ns = self.spent_ns.saturating_add(duration_ns);
+        self.last_pass_ns.insert(label.to_owned(), duration_ns);
+    }
+    /// Forgets how long `label` took, so it is tried again next time instead
+    /// of being skipped forever.
+    pub(crate) fn forget(&mut self, label: &str) {
+        self.last_pass_ns.remove(label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn never_recorded_passes_run() {
+        let budget = FrameBudget::new(0);
+        assert!(!budget.should_skip_pass("bloom", u64::MAX));
+    }
+
+    #[test]
+    fn recorded_passes_are_skipped_over_budget() {
+        let mut budget = FrameBudget::new(1_000);
+        budget.record("bloom", 400);
+        assert!(!budget.should_skip_pass("bloom", 600));
+        assert!(budget.should_skip_pass("bloom", 601));
+    }
+
+    #[test]
+    fn recorded_passes_are_skipped_over_their_limit() {
+        let mut budget = FrameBudget::new(1_000);
+        budget.per_pass_limits.insert("bloom".to_owned(), 300);
+        budget.record("bloom", 300);
+        assert!(!budget.should_skip_pass("bloom", 0));
+        budget.record("bloom", 301);
+        assert!(budget.should_skip_pass("bloom", 0));
+    }
+
+    #[test]
+    fn skipped_passes_forget_their_estimate() {
+        let mut budget = FrameBudget::new(1_000);
+        budget.record("bloom", 2_000);
+        assert!(budget.should_skip_pass("bloom", 0));
+        budget.forget("bloom");
+        assert!(!budget.should_skip_pass("bloom", 0));
+    }
+
+    #[test]
+    fn reset_is_required_each_frame() {
+        let mut budget = FrameBudget::new(1_000);
+        budget.record("scene", 600);
+        budget.record("scene", 600);
+        assert_eq!(budget.spent_ns(), 1_200);
+        budget.reset();
+        assert_eq!(budget.spent_ns(), 0);
+        // the estimate survives the reset
+        assert!(budget.should_skip_pass("scene", 500));
+    }
+
+    #[test]
+    fn times_saturate() {
+        let mut budget = FrameBudget::new(u64::MAX);
+        budget.record("scene", u64::MAX);
+        budget.record("scene", 1);
+        assert_eq!(budget.spent_ns(), u64::MAX);
+        budget.record("scene", u64::MAX);
+        assert!(!budget.should_skip_pass("scene", u64::MAX));
+    }
+}
@@ -0,0 +1,84 @@
+use wgpu::Color;
+
+/// Conversions between linear and sRGB encoded colors.
+///
+/// Colors passed to the renderer are linear. Use `from_srgb_u8` for colors
+/// picked in image editors, which are sRGB encoded.
+pub trait ColorExt {
+    fn from_srgb_u8(r: u8, g: u8, b: u8, a: u8) -> Self;
+    /// Encodes the color channels to sRGB, alpha stays linear.
+    fn to_srgb(&self) -> Self;
+}
+
+fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+impl ColorExt for Color {
+    fn from_srgb_u8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color {
+            r: srgb_to_linear(r as f64 / 255.0),
+            g: srgb_to_linear(g as f64 / 255.0),
+            b: srgb_to_linear(b as f64 / 255.0),
+            a: a as f64 / 255.0,
+        }
+    }
+    fn to_srgb(&self) -> Self {
+        Color {
+            r: linear_to_srgb(self.r),
+            g: linear_to_srgb(self.g),
+            b: linear_to_srgb(self.b),
+            a: self.a,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-3, "{} is not close to {}", a, b);
+    }
+
+    #[test]
+    fn srgb_u8_round_trips() {
+        for value in 0..=255 {
+            let color = Color::from_srgb_u8(value, value, value, value).to_srgb();
+            for channel in [color.r, color.g, color.b, color.a] {
+                assert_eq!((channel * 255.0).round() as u8, value);
+            }
+        }
+    }
+
+    #[test]
+    fn known_values() {
+        let color = Color::from_srgb_u8(0, 188, 255, 128);
+        assert_close(color.r, 0.0);
+        assert_close(color.g, 0.503);
+        assert_close(color.b, 1.0);
+        // alpha is not encoded
+        assert_close(color.a, 128.0 / 255.0);
+        let gray = Color {
+            r: 0.5,
+            g: 0.5,
+            b: 0.5,
+            a: 0.5,
+        }
+        .to_srgb();
+        assert_close(gray.r * 255.0, 187.516);
+        assert_close(gray.a, 0.5);
+    }
+}
@@ -0,0 +1,55 @@
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RendererEvent {
+    /// The surface was resized, in physical pixels.
+    Resized { width: u32, height: u32 },
+    /// Not sent yet, wgpu 0.10 doesn't report device loss.
+    DeviceLost,
+    /// A swapchain frame was acquired, `index` counts up from 0.
+    FrameBegin { index: u64 },
+    /// The last acquired frame was submitted and presented.
+    FrameEnd,
+}
+
+type Subscriber = Box<dyn Fn(&RendererEvent) + Send>;
+
+/// Calls every subscriber when the renderer sends an event, so subsystems
+/// can react to e.g. resizes without the renderer knowing about them.
+#[derive(Default)]
+pub struct RendererEventBus {
+    subscribers: Mutex<Vec<Subscriber>>,
+    frame_index: AtomicU64,
+}
+
+impl RendererEventBus {
+    /// Subscribers are called in the order they subscribed.
+    ///
+    /// They must not subscribe from inside the callback, that deadlocks.
+    pub fn subscribe(&self, callback: Subscriber) {
+        self.subscribers.lock().unwrap().push(callback);
+    }
+    pub(crate) fn send(&self, event: RendererEvent) {
+        for subscriber in self.subscribers.lock().unwrap().iter() {
+            subscriber(&event);
+        }
+    }
+    pub(crate) fn next_frame_index(&self) -> u64 {
+        self.frame_index.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for RendererEventBus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RendererEventBus")
+            .field("subscribers", &self.subscribers.lock().unwrap().len())
+            .field("frame_index", &self.frame_index)
+            .finish()
+    }
+}
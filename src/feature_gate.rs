@@ -0,0 +1,51 @@
+use std::{error::Error, fmt};
+
+use wgpu::Features;
+
+use crate::renderer::Renderer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureNotAvailableError {
+    pub missing: Features,
+}
+
+impl fmt::Display for FeatureNotAvailableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the device does not have the required features {:?}",
+            self.missing
+        )
+    }
+}
+
+impl Error for FeatureNotAvailableError {}
+
+/// Checks for the features `F`, given as `Features::bits()`, up front instead
+/// of failing validation later.
+///
+/// Bits that aren't a `Features` flag fail to compile once `ensure` or
+/// `FEATURES` is used.
+///
+/// ```ignore
+/// FeatureGated::<{ Features::NON_FILL_POLYGON_MODE.bits() }>::ensure(&renderer)?;
+/// ```
+pub struct FeatureGated<const F: u64> {
+    _private: (),
+}
+
+impl<const F: u64> FeatureGated<F> {
+    pub const FEATURES: Features = match Features::from_bits(F) {
+        Some(features) => features,
+        None => panic!("F contains bits that are not wgpu features"),
+    };
+
+    pub fn ensure(renderer: &Renderer) -> Result<(), FeatureNotAvailableError> {
+        let missing = Self::FEATURES - renderer.capabilities().features;
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(FeatureNotAvailableError { missing })
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use winit::event::Event;
+use winit_input_helper::WinitInputHelper;
+
+/// Collects winit events into queryable input state.
+///
+/// Instead of matching on nested `Event::WindowEvent { event, .. }` arms,
+/// feed every event to `update` and, once it returns `true`, query the
+/// frame's input through method calls on `input()`:
+/// ```ignore
+/// event_loop.run(move |event, _, control_flow| {
+///     if input.update(&event) {
+///         if input.input().quit() {
+///             *control_flow = ControlFlow::Exit;
+///         }
+///     }
+/// });
+/// ```
+pub struct WinitInputHelperIntegration {
+    input: WinitInputHelper,
+}
+
+impl WinitInputHelperIntegration {
+    pub fn new() -> Self {
+        Self {
+            input: WinitInputHelper::new(),
+        }
+    }
+    /// Processes an event.
+    ///
+    /// Returns `true` once all events of the current frame have been
+    /// processed (on `Event::MainEventsCleared`), meaning the input state is
+    /// ready to be queried.
+    pub fn update(&mut self, event: &Event<()>) -> bool {
+        self.input.update(event)
+    }
+    pub fn input(&self) -> &WinitInputHelper {
+        &self.input
+    }
+}
+
+impl Default for WinitInputHelperIntegration {
+    fn default() -> Self {
+        Self::new()
+    }
+}
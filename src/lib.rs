@@ -0,0 +1,22 @@
+pub mod budget;
+pub mod color;
+pub mod event_bus;
+pub mod feature_gate;
+#[cfg(feature = "winit_input_helper")]
+pub mod input;
+pub mod material;
+pub mod memory;
+pub mod pipeline;
+pub mod procedural_texture;
+pub mod reflection;
+pub mod renderer;
+pub mod shader;
+pub mod sky;
+#[cfg(test)]
+mod test_support;
+
+/// Logs warnings and errors to stdout.
+#[cfg(feature = "simple-logger")]
+pub fn default_logger() -> Result<(), log::SetLoggerError> {
+    simple_logger::init_with_level(log::Level::Warn)
+}
@@ -0,0 +1,302 @@
+use std::{error::Error, fmt, ops::Range};
+
+use wgpu::{BindGroupLayout, Buffer};
+
+use crate::reflection::{
+    BindingReflection, ResourceKind, ScalarKind, ShaderType, StructMemberReflection,
+};
+use crate::renderer::BindGroupId;
+
+/// A value that can be written into a field of a material's uniform block.
+pub trait MaterialValue {
+    fn shader_type() -> ShaderType;
+    fn write_bytes(&self, out: &mut Vec<u8>);
+}
+
+macro_rules! impl_scalar_value {
+    ($ty:ty, $kind:expr) => {
+        impl MaterialValue for $ty {
+            fn shader_type() -> ShaderType {
+                ShaderType::Scalar {
+                    kind: $kind,
+                    width: 4,
+                }
+            }
+            fn write_bytes(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+    };
+}
+
+impl_scalar_value!(f32, ScalarKind::Float);
+impl_scalar_value!(i32, ScalarKind::Sint);
+impl_scalar_value!(u32, ScalarKind::Uint);
+
+macro_rules! impl_vector_value {
+    ($size:expr) => {
+        impl MaterialValue for [f32; $size] {
+            fn shader_type() -> ShaderType {
+                ShaderType::Vector {
+                    size: $size,
+                    kind: ScalarKind::Float,
+                    width: 4,
+                }
+            }
+            fn write_bytes(&self, out: &mut Vec<u8>) {
+                for component in self {
+                    component.write_bytes(out);
+                }
+            }
+        }
+    };
+}
+
+impl_vector_value!(2);
+impl_vector_value!(3);
+impl_vector_value!(4);
+
+/// Column-major, like WGSL.
+impl MaterialValue for [[f32; 4]; 4] {
+    fn shader_type() -> ShaderType {
+        ShaderType::Matrix {
+            columns: 4,
+            rows: 4,
+            width: 4,
+        }
+    }
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        for column in self {
+            column.write_bytes(out);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaterialError {
+    /// The shader has no uniform struct at this group/binding.
+    ///
+    /// `shader` describes the shader resource, see `ResourceInfo`.
+    NoUniformBlock {
+        shader: String,
+        group: u32,
+        binding: u32,
+    },
+    UnknownField {
+        field: String,
+        available: Vec<String>,
+    },
+    /// `expected` and `found` are the WGSL spelling of the types.
+    TypeMismatch {
+        field: String,
+        expected: String,
+        found: String,
+    },
+}
+
+impl fmt::Display for MaterialError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MaterialError::NoUniformBlock {
+                shader,
+                group,
+                binding,
+            } => write!(
+                f,
+                "{} has no uniform struct at group {}, binding {}",
+                shader, group, binding
+            ),
+            MaterialError::UnknownField { field, available } => write!(
+                f,
+                "material has no field `{}`, available fields are: {}",
+                field,
+                available.join(", ")
+            ),
+            MaterialError::TypeMismatch {
+                field,
+                expected,
+                found,
+            } => write!(
+                f,
+                "material field `{}` is of type `{}`, but `{}` was given",
+                field, expected, found
+            ),
+        }
+    }
+}
+
+impl Error for MaterialError {}
+
+/// The CPU copy of a uniform block, with the range written since the last
+/// upload.
+#[derive(Debug, Clone)]
+pub(crate) struct UniformBlock {
+    pub(crate) fields: Vec<StructMemberReflection>,
+    pub(crate) data: Vec<u8>,
+    pub(crate) dirty: Option<Range<usize>>,
+}
+
+impl UniformBlock {
+    /// `None` if `binding` is not a uniform struct.
+    pub(crate) fn from_binding(binding: &BindingReflection) -> Option<Self> {
+        match (binding.resource, &binding.ty) {
+            (ResourceKind::UniformBuffer, ShaderType::Struct { members, size, .. }) => Some(Self {
+                fields: members.clone(),
+                data: vec![0; *size as usize],
+                dirty: None,
+            }),
+            _ => None,
+        }
+    }
+    pub(crate) fn set<T: MaterialValue>(
+        &mut self,
+        name: &str,
+        value: T,
+    ) -> Result<(), MaterialError> {
+        let field = self
+            .fields
+            .iter()
+            .find(|field| field.name.as_deref() == Some(name))
+            .ok_or_else(|| MaterialError::UnknownField {
+                field: name.to_owned(),
+                available: self.field_names().map(str::to_owned).collect(),
+            })?;
+        if field.ty != T::shader_type() {
+            return Err(MaterialError::TypeMismatch {
+                field: name.to_owned(),
+                expected: field.ty.to_string(),
+                found: T::shader_type().to_string(),
+            });
+        }
+        let mut bytes = Vec::new();
+        value.write_bytes(&mut bytes);
+        let start = field.offset as usize;
+        let end = start + bytes.len();
+        self.data[start..end].copy_from_slice(&bytes);
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.start.min(start)..dirty.end.max(end),
+            None => start..end,
+        });
+        Ok(())
+    }
+    pub(crate) fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().filter_map(|field| field.name.as_deref())
+    }
+}
+
+/// A uniform block whose fields are set by name.
+///
+/// Writes are collected on the CPU and uploaded together by
+/// `Renderer::flush_material`.
+pub struct Material {
+    pub(crate) block: UniformBlock,
+    pub(crate) buffer: Buffer,
+    pub(crate) bind_group_layout: BindGroupLayout,
+    pub(crate) bind_group: BindGroupId,
+    pub(crate) group: u32,
+}
+
+impl Material {
+    /// Sets the field `name` of the uniform block.
+    ///
+    /// The value is only uploaded on the next `Renderer::flush_material`.
+    pub fn set<T: MaterialValue>(&mut self, name: &str, value: T) -> Result<(), MaterialError> {
+        self.block.set(name, value)
+    }
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.block.field_names()
+    }
+    /// The layout to put at index `group()` of the pipeline layout.
+    pub fn bind_group_layout(&self) -> &BindGroupLayout {
+        &self.bind_group_layout
+    }
+    pub fn bind_group(&self) -> BindGroupId {
+        self.bind_group
+    }
+    pub fn group(&self) -> u32 {
+        self.group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reflection::ShaderReflection;
+
+    const BLOCK: &str = "
+[[block]]
+struct Params {
+    tint: vec4<f32>;
+    roughness: f32;
+    flags: u32;
+    offset: vec2<f32>;
+    normal: vec3<f32>;
+};
+
+[[group(0), binding(0)]] var<uniform> params: Params;
+[[group(0), binding(1)]] var color: texture_2d<f32>;
+";
+
+    fn block(binding: u32) -> Option<UniformBlock> {
+        let module = naga::front::wgsl::parse_str(BLOCK).unwrap();
+        let reflection = ShaderReflection::from_module(&module);
+        UniformBlock::from_binding(reflection.binding(0, binding).unwrap())
+    }
+
+    #[test]
+    fn offsets_come_from_reflection() {
+        let mut block = block(0).unwrap();
+        assert_eq!(block.data.len(), 48);
+        block.set("roughness", 0.5f32).unwrap();
+        assert_eq!(block.data[16..20], 0.5f32.to_le_bytes());
+        assert_eq!(block.dirty, Some(16..20));
+        block.set("normal", [1.0f32, 2.0, 3.0]).unwrap();
+        assert_eq!(block.data[32..36], 1.0f32.to_le_bytes());
+        assert_eq!(block.data[40..44], 3.0f32.to_le_bytes());
+    }
+
+    #[test]
+    fn dirty_ranges_merge() {
+        let mut block = block(0).unwrap();
+        block.set("flags", 1u32).unwrap();
+        assert_eq!(block.dirty, Some(20..24));
+        block.set("tint", [1.0f32; 4]).unwrap();
+        assert_eq!(block.dirty, Some(0..24));
+        block.set("offset", [0.0f32; 2]).unwrap();
+        assert_eq!(block.dirty, Some(0..32));
+    }
+
+    #[test]
+    fn type_mismatch_reports_the_field_type() {
+        let mut block = block(0).unwrap();
+        assert_eq!(
+            block.set("normal", 1.0f32),
+            Err(MaterialError::TypeMismatch {
+                field: "normal".to_owned(),
+                expected: "vec3<f32>".to_owned(),
+                found: "f32".to_owned(),
+            })
+        );
+        assert_eq!(block.dirty, None);
+    }
+
+    #[test]
+    fn unknown_field_lists_the_fields() {
+        let mut block = block(0).unwrap();
+        assert_eq!(
+            block.set("metalness", 1.0f32),
+            Err(MaterialError::UnknownField {
+                field: "metalness".to_owned(),
+                available: ["tint", "roughness", "flags", "offset", "normal"]
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect(),
+            })
+        );
+    }
+
+    #[test]
+    fn only_uniform_structs_are_blocks() {
+        assert!(block(1).is_none());
+    }
+}
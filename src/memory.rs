@@ -0,0 +1,27 @@
+/// Bytes of GPU memory the renderer has allocated for its resources.
+///
+/// These are the sizes requested from wgpu, drivers may allocate more for
+/// alignment and padding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryTracker {
+    texture_bytes: u64,
+    buffer_bytes: u64,
+}
+
+impl MemoryTracker {
+    pub fn gpu_allocated_bytes(&self) -> u64 {
+        self.texture_bytes + self.buffer_bytes
+    }
+    pub fn texture_bytes(&self) -> u64 {
+        self.texture_bytes
+    }
+    pub fn buffer_bytes(&self) -> u64 {
+        self.buffer_bytes
+    }
+    pub(crate) fn track_texture(&mut self, bytes: u64) {
+        self.texture_bytes += bytes;
+    }
+    pub(crate) fn track_buffer(&mut self, bytes: u64) {
+        self.buffer_bytes += bytes;
+    }
+}
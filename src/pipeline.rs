@@ -0,0 +1,112 @@
+use std::{error::Error, fmt};
+
+use wgpu::{
+    Face, FrontFace, IndexFormat, PipelineLayout, PolygonMode, PrimitiveState, PrimitiveTopology,
+};
+
+use crate::renderer::ShaderId;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderPipelineError {
+    /// Primitive restart was requested for a list topology, where it is
+    /// undefined.
+    RestartWithoutStripTopology { topology: PrimitiveTopology },
+}
+
+impl fmt::Display for RenderPipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderPipelineError::RestartWithoutStripTopology { topology } => write!(
+                f,
+                "primitive restart requires a strip topology, but the topology is {:?}",
+                topology
+            ),
+        }
+    }
+}
+
+impl Error for RenderPipelineError {}
+
+/// Describes a render pipeline for `Renderer::build_render_pipeline`.
+///
+/// The shader's `vs_main` and `fs_main` entry points are used, see
+/// `fragment_shader` to take them from separate shaders.
+///
+/// Defaults to a triangle list with back-face culling, the same as
+/// `Renderer::create_render_pipeline`.
+pub struct RenderPipelineDescriptorBuilder<'a> {
+    pub(crate) layout: &'a PipelineLayout,
+    pub(crate) shader: ShaderId,
+    pub(crate) fragment_shader: Option<ShaderId>,
+    pub(crate) label: Option<&'a str>,
+    pub(crate) wireframe_variant: bool,
+    topology: PrimitiveTopology,
+    strip_index_format_with_restart: Option<IndexFormat>,
+}
+
+impl<'a> RenderPipelineDescriptorBuilder<'a> {
+    pub fn new(layout: &'a PipelineLayout, shader: ShaderId) -> Self {
+        Self {
+            layout,
+            shader,
+            fragment_shader: None,
+            label: None,
+            wireframe_variant: false,
+            topology: PrimitiveTopology::TriangleList,
+            strip_index_format_with_restart: None,
+        }
+    }
+    /// Shows up in wgpu's error messages and `Renderer::dump_resources`,
+    /// defaults to `Pipeline #<index>`.
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+    /// Takes `fs_main` from `shader` instead of the shader given to `new`,
+    /// which then only has to provide `vs_main`, e.g. for a pair of GLSL
+    /// files.
+    pub fn fragment_shader(mut self, shader: ShaderId) -> Self {
+        self.fragment_shader = Some(shader);
+        self
+    }
+    /// Also compiles the pipeline with `PolygonMode::Line`, so
+    /// `Renderer::toggle_wireframe` can switch to it.
+    ///
+    /// Ignored if the adapter doesn't support `NON_FILL_POLYGON_MODE` or the
+    /// topology doesn't draw triangles.
+    pub fn wireframe_variant(mut self) -> Self {
+        self.wireframe_variant = true;
+        self
+    }
+    pub fn topology(mut self, topology: PrimitiveTopology) -> Self {
+        self.topology = topology;
+        self
+    }
+    /// Restarts the strip whenever the index buffer contains the maximum value
+    /// of `format`, i.e. `0xFFFF` or `0xFFFFFFFF`.
+    ///
+    /// Only valid for `TriangleStrip` and `LineStrip` topologies.
+    pub fn strip_index_format_with_restart(mut self, format: IndexFormat) -> Self {
+        self.strip_index_format_with_restart = Some(format);
+        self
+    }
+    pub(crate) fn primitive_state(&self) -> Result<PrimitiveState, RenderPipelineError> {
+        if self.strip_index_format_with_restart.is_some() {
+            match self.topology {
+                PrimitiveTopology::TriangleStrip | PrimitiveTopology::LineStrip => (),
+                topology => {
+                    return Err(RenderPipelineError::RestartWithoutStripTopology { topology })
+                }
+            }
+        }
+        Ok(PrimitiveState {
+            topology: self.topology,
+            strip_index_format: self.strip_index_format_with_restart,
+            front_face: FrontFace::Ccw,
+            cull_mode: Some(Face::Back),
+            polygon_mode: PolygonMode::Fill,
+            clamp_depth: false,
+            conservative: false,
+        })
+    }
+}
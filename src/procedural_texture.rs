@@ -0,0 +1,103 @@
+//! Placeholder textures, as tightly packed RGBA bytes for
+//! `Renderer::create_texture_array`.
+
+/// A `size` by `size` checkerboard of `cell_size` sized cells, starting with
+/// `color_a` in the top left.
+pub fn checkerboard(size: u32, cell_size: u32, color_a: [u8; 4], color_b: [u8; 4]) -> Vec<u8> {
+    assert!(cell_size > 0, "cell_size must not be 0");
+    pixels(size, size, |x, y| {
+        if ((x / cell_size) ^ (y / cell_size)) & 1 == 0 {
+            color_a
+        } else {
+            color_b
+        }
+    })
+}
+
+/// Fades from `left` to `right`, interpolating the bytes as they are.
+pub fn gradient_horizontal(width: u32, height: u32, left: [u8; 4], right: [u8; 4]) -> Vec<u8> {
+    pixels(width, height, |x, _| {
+        let t = (x as f32 + 0.5) / width as f32;
+        let mut color = [0; 4];
+        for ((out, left), right) in color.iter_mut().zip(left).zip(right) {
+            *out = (left as f32 + (right as f32 - left as f32) * t).round() as u8;
+        }
+        color
+    })
+}
+
+/// Shows the texture coordinates of each pixel, red is U and green is V.
+pub fn uv_debug(width: u32, height: u32) -> Vec<u8> {
+    pixels(width, height, |x, y| {
+        let u = (x as f32 + 0.5) / width as f32;
+        let v = (y as f32 + 0.5) / height as f32;
+        [(u * 255.0).round() as u8, (v * 255.0).round() as u8, 0, 255]
+    })
+}
+
+fn pixels(width: u32, height: u32, color: impl Fn(u32, u32) -> [u8; 4]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(width as usize * height as usize * 4);
+    for y in 0..height {
+        for x in 0..width {
+            bytes.extend_from_slice(&color(x, y));
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    fn pixel(bytes: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+        let start = (y * width + x) as usize * 4;
+        let mut color = [0; 4];
+        color.copy_from_slice(&bytes[start..start + 4]);
+        color
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(checkerboard(8, 2, RED, BLUE).len(), 8 * 8 * 4);
+        assert_eq!(gradient_horizontal(5, 3, RED, BLUE).len(), 5 * 3 * 4);
+        assert_eq!(uv_debug(7, 2).len(), 7 * 2 * 4);
+        assert!(uv_debug(0, 4).is_empty());
+    }
+
+    #[test]
+    fn checkerboard_cells() {
+        let bytes = checkerboard(4, 2, RED, BLUE);
+        assert_eq!(pixel(&bytes, 4, 0, 0), RED);
+        assert_eq!(pixel(&bytes, 4, 1, 1), RED);
+        assert_eq!(pixel(&bytes, 4, 2, 0), BLUE);
+        assert_eq!(pixel(&bytes, 4, 0, 2), BLUE);
+        assert_eq!(pixel(&bytes, 4, 3, 3), RED);
+    }
+
+    #[test]
+    #[should_panic(expected = "cell_size must not be 0")]
+    fn checkerboard_empty_cells() {
+        checkerboard(4, 0, RED, BLUE);
+    }
+
+    #[test]
+    fn gradient_endpoints() {
+        let bytes = gradient_horizontal(256, 1, [0; 4], [255; 4]);
+        // pixel centers are sampled, so the ends are half a pixel inside
+        assert_eq!(pixel(&bytes, 256, 0, 0), [0; 4]);
+        assert_eq!(pixel(&bytes, 256, 255, 0), [255; 4]);
+        let bytes = gradient_horizontal(1, 1, [0; 4], [200; 4]);
+        assert_eq!(pixel(&bytes, 1, 0, 0), [100; 4]);
+    }
+
+    #[test]
+    fn uv_debug_channels() {
+        let bytes = uv_debug(2, 2);
+        assert_eq!(pixel(&bytes, 2, 0, 0), [64, 64, 0, 255]);
+        assert_eq!(pixel(&bytes, 2, 1, 0), [191, 64, 0, 255]);
+        assert_eq!(pixel(&bytes, 2, 0, 1), [64, 191, 0, 255]);
+    }
+}
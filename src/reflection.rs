@@ -0,0 +1,799 @@
+use naga::{ArraySize, Binding, ConstantInner, Handle, Module, ScalarValue, Type, TypeInner};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use wgpu::{
+    BindingType, BufferBindingType, BufferSize, TextureFormat, TextureSampleType,
+    TextureViewDimension, VertexFormat,
+};
+
+/// Everything a shader expects from the outside, as seen by naga.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ShaderReflection {
+    pub entry_points: Vec<EntryPointReflection>,
+    pub bindings: Vec<BindingReflection>,
+    pub overrides: Vec<OverrideReflection>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+    Compute,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EntryPointReflection {
+    pub name: String,
+    pub stage: ShaderStage,
+    /// Only populated for vertex entry points.
+    pub vertex_inputs: Vec<VertexInputReflection>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VertexInputReflection {
+    pub location: u32,
+    pub name: Option<String>,
+    pub ty: ShaderType,
+}
+
+impl VertexInputReflection {
+    /// The vertex format matching this input, if there is one.
+    pub fn vertex_format(&self) -> Option<VertexFormat> {
+        self.ty.vertex_format()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BindingReflection {
+    pub group: u32,
+    pub binding: u32,
+    pub name: Option<String>,
+    pub resource: ResourceKind,
+    pub ty: ShaderType,
+}
+
+impl BindingReflection {
+    /// The binding type a `BindGroupLayoutEntry` for this binding needs, if
+    /// there is one.
+    ///
+    /// Float textures are assumed to be filterable, except multisampled ones,
+    /// which WebGPU requires to be unfilterable, and samplers filtering.
+    pub fn binding_type(&self) -> Option<BindingType> {
+        Some(match (self.resource, &self.ty) {
+            (ResourceKind::UniformBuffer, _) => BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: self.ty.min_binding_size(),
+            },
+            (ResourceKind::StorageBuffer { read_only }, _) => BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: self.ty.min_binding_size(),
+            },
+            (
+                ResourceKind::Texture,
+                ShaderType::Texture {
+                    dimension,
+                    arrayed,
+                    class,
+                },
+            ) => {
+                let (sample_type, multisampled) = match *class {
+                    TextureClass::Sampled { kind, multisampled } => (
+                        match kind {
+                            ScalarKind::Float => TextureSampleType::Float {
+                                filterable: !multisampled,
+                            },
+                            ScalarKind::Sint => TextureSampleType::Sint,
+                            ScalarKind::Uint => TextureSampleType::Uint,
+                            ScalarKind::Bool => return None,
+                        },
+                        multisampled,
+                    ),
+                    TextureClass::Depth { multisampled } => {
+                        (TextureSampleType::Depth, multisampled)
+                    }
+                    TextureClass::Storage { .. } => return None,
+                };
+                BindingType::Texture {
+                    sample_type,
+                    view_dimension: dimension.view_dimension(*arrayed),
+                    multisampled,
+                }
+            }
+            (
+                ResourceKind::StorageTexture,
+                ShaderType::Texture {
+                    dimension,
+                    arrayed,
+                    class: TextureClass::Storage { format, access },
+                },
+            ) => BindingType::StorageTexture {
+                access: access.to_wgpu(),
+                format: format.texture_format(),
+                view_dimension: dimension.view_dimension(*arrayed),
+            },
+            (ResourceKind::Sampler, ShaderType::Sampler { comparison }) => BindingType::Sampler {
+                filtering: true,
+                comparison: *comparison,
+            },
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ResourceKind {
+    UniformBuffer,
+    StorageBuffer {
+        read_only: bool,
+    },
+    /// A sampled or depth texture.
+    Texture,
+    StorageTexture,
+    Sampler,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OverrideReflection {
+    pub id: u32,
+    pub name: Option<String>,
+    pub kind: ScalarKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScalarKind {
+    Sint,
+    Uint,
+    Float,
+    Bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextureDimension {
+    D1,
+    D2,
+    D3,
+    Cube,
+}
+
+impl TextureDimension {
+    pub fn view_dimension(self, arrayed: bool) -> TextureViewDimension {
+        match (self, arrayed) {
+            (TextureDimension::D1, _) => TextureViewDimension::D1,
+            (TextureDimension::D2, false) => TextureViewDimension::D2,
+            (TextureDimension::D2, true) => TextureViewDimension::D2Array,
+            (TextureDimension::D3, _) => TextureViewDimension::D3,
+            (TextureDimension::Cube, false) => TextureViewDimension::Cube,
+            (TextureDimension::Cube, true) => TextureViewDimension::CubeArray,
+        }
+    }
+}
+
+/// What a texture holds and how it is accessed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextureClass {
+    /// Sampled as `kind`, e.g. `texture_2d<f32>`.
+    Sampled {
+        kind: ScalarKind,
+        multisampled: bool,
+    },
+    /// Sampled with comparisons, e.g. `texture_depth_2d`.
+    Depth { multisampled: bool },
+    /// Read or written texel by texel, e.g. `texture_storage_2d<rgba8unorm>`.
+    Storage {
+        format: StorageTextureFormat,
+        access: StorageTextureAccess,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StorageTextureAccess {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+}
+
+impl StorageTextureAccess {
+    pub fn to_wgpu(self) -> wgpu::StorageTextureAccess {
+        match self {
+            StorageTextureAccess::ReadOnly => wgpu::StorageTextureAccess::ReadOnly,
+            StorageTextureAccess::WriteOnly => wgpu::StorageTextureAccess::WriteOnly,
+            StorageTextureAccess::ReadWrite => wgpu::StorageTextureAccess::ReadWrite,
+        }
+    }
+}
+
+impl fmt::Display for StorageTextureAccess {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            StorageTextureAccess::ReadOnly => "read",
+            StorageTextureAccess::WriteOnly => "write",
+            StorageTextureAccess::ReadWrite => "read_write",
+        })
+    }
+}
+
+macro_rules! storage_texture_formats {
+    ($($format:ident),* $(,)?) => {
+        /// The formats storage textures can have, named like `TextureFormat`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+        pub enum StorageTextureFormat {
+            $($format,)*
+        }
+
+        impl StorageTextureFormat {
+            pub fn texture_format(self) -> TextureFormat {
+                match self {
+                    $(StorageTextureFormat::$format => TextureFormat::$format,)*
+                }
+            }
+        }
+
+        impl From<naga::StorageFormat> for StorageTextureFormat {
+            fn from(format: naga::StorageFormat) -> Self {
+                match format {
+                    $(naga::StorageFormat::$format => Self::$format,)*
+                }
+            }
+        }
+    };
+}
+
+storage_texture_formats!(
+    R8Unorm,
+    R8Snorm,
+    R8Uint,
+    R8Sint,
+    R16Uint,
+    R16Sint,
+    R16Float,
+    Rg8Unorm,
+    Rg8Snorm,
+    Rg8Uint,
+    Rg8Sint,
+    R32Uint,
+    R32Sint,
+    R32Float,
+    Rg16Uint,
+    Rg16Sint,
+    Rg16Float,
+    Rgba8Unorm,
+    Rgba8Snorm,
+    Rgba8Uint,
+    Rgba8Sint,
+    Rgb10a2Unorm,
+    Rg11b10Float,
+    Rg32Uint,
+    Rg32Sint,
+    Rg32Float,
+    Rgba16Uint,
+    Rgba16Sint,
+    Rgba16Float,
+    Rgba32Uint,
+    Rgba32Sint,
+    Rgba32Float,
+);
+
+/// Formats the format as it would be spelled in WGSL, e.g. `rgba8unorm`.
+impl fmt::Display for StorageTextureFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&format!("{:?}", self).to_lowercase())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StructMemberReflection {
+    pub name: Option<String>,
+    pub offset: u32,
+    pub ty: ShaderType,
+}
+
+/// A shader type, simplified from naga's type arena.
+///
+/// Types that have no stable description (pointers, atomics, ...) are still
+/// reported, rather than failing the whole reflection.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ShaderType {
+    Scalar {
+        kind: ScalarKind,
+        width: u8,
+    },
+    Vector {
+        size: u8,
+        kind: ScalarKind,
+        width: u8,
+    },
+    Matrix {
+        columns: u8,
+        rows: u8,
+        width: u8,
+    },
+    Atomic {
+        kind: ScalarKind,
+        width: u8,
+    },
+    /// `len` is `None` for runtime-sized arrays.
+    Array {
+        base: Box<ShaderType>,
+        len: Option<u32>,
+        stride: u32,
+    },
+    Struct {
+        name: Option<String>,
+        members: Vec<StructMemberReflection>,
+        size: u32,
+    },
+    Texture {
+        dimension: TextureDimension,
+        arrayed: bool,
+        class: TextureClass,
+    },
+    Sampler {
+        comparison: bool,
+    },
+    /// Anything else, as naga's debug description.
+    Other(String),
+}
+
+impl ShaderType {
+    /// The size of a struct, as the `min_binding_size` of its buffer.
+    fn min_binding_size(&self) -> Option<BufferSize> {
+        match self {
+            ShaderType::Struct { size, .. } => BufferSize::new(*size as u64),
+            _ => None,
+        }
+    }
+    pub fn vertex_format(&self) -> Option<VertexFormat> {
+        use ScalarKind::*;
+        Some(match *self {
+            ShaderType::Scalar {
+                kind: Float,
+                width: 4,
+            } => VertexFormat::Float32,
+            ShaderType::Scalar {
+                kind: Uint,
+                width: 4,
+            } => VertexFormat::Uint32,
+            ShaderType::Scalar {
+                kind: Sint,
+                width: 4,
+            } => VertexFormat::Sint32,
+            ShaderType::Vector {
+                size,
+                kind,
+                width: 4,
+            } => match (size, kind) {
+                (2, Float) => VertexFormat::Float32x2,
+                (3, Float) => VertexFormat::Float32x3,
+                (4, Float) => VertexFormat::Float32x4,
+                (2, Uint) => VertexFormat::Uint32x2,
+                (3, Uint) => VertexFormat::Uint32x3,
+                (4, Uint) => VertexFormat::Uint32x4,
+                (2, Sint) => VertexFormat::Sint32x2,
+                (3, Sint) => VertexFormat::Sint32x3,
+                (4, Sint) => VertexFormat::Sint32x4,
+                _ => return None,
+            },
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for ScalarKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ScalarKind::Sint => "i32",
+            ScalarKind::Uint => "u32",
+            ScalarKind::Float => "f32",
+            ScalarKind::Bool => "bool",
+        })
+    }
+}
+
+/// Formats the type as it would be spelled in WGSL.
+impl fmt::Display for ShaderType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShaderType::Scalar { kind, .. } => write!(f, "{}", kind),
+            ShaderType::Vector { size, kind, .. } => write!(f, "vec{}<{}>", size, kind),
+            ShaderType::Matrix { columns, rows, .. } => write!(f, "mat{}x{}<f32>", columns, rows),
+            ShaderType::Atomic { kind, .. } => write!(f, "atomic<{}>", kind),
+            ShaderType::Array {
+                base,
+                len: Some(len),
+                ..
+            } => write!(f, "array<{}, {}>", base, len),
+            ShaderType::Array {
+                base, len: None, ..
+            } => write!(f, "array<{}>", base),
+            ShaderType::Struct { name, .. } => f.write_str(name.as_deref().unwrap_or("struct")),
+            ShaderType::Texture {
+                dimension,
+                arrayed,
+                class,
+            } => {
+                let dimension = match dimension {
+                    TextureDimension::D1 => "1d",
+                    TextureDimension::D2 => "2d",
+                    TextureDimension::D3 => "3d",
+                    TextureDimension::Cube => "cube",
+                };
+                let arrayed = if *arrayed { "_array" } else { "" };
+                match class {
+                    TextureClass::Sampled {
+                        kind,
+                        multisampled: false,
+                    } => write!(f, "texture_{}{}<{}>", dimension, arrayed, kind),
+                    TextureClass::Sampled {
+                        kind,
+                        multisampled: true,
+                    } => write!(f, "texture_multisampled_{}{}<{}>", dimension, arrayed, kind),
+                    TextureClass::Depth {
+                        multisampled: false,
+                    } => write!(f, "texture_depth_{}{}", dimension, arrayed),
+                    TextureClass::Depth { multisampled: true } => {
+                        write!(f, "texture_depth_multisampled_{}{}", dimension, arrayed)
+                    }
+                    TextureClass::Storage { format, access } => write!(
+                        f,
+                        "texture_storage_{}{}<{}, {}>",
+                        dimension, arrayed, format, access
+                    ),
+                }
+            }
+            ShaderType::Sampler { comparison: false } => f.write_str("sampler"),
+            ShaderType::Sampler { comparison: true } => f.write_str("sampler_comparison"),
+            ShaderType::Other(description) => f.write_str(description),
+        }
+    }
+}
+
+impl From<naga::ScalarKind> for ScalarKind {
+    fn from(kind: naga::ScalarKind) -> Self {
+        match kind {
+            naga::ScalarKind::Sint => Self::Sint,
+            naga::ScalarKind::Uint => Self::Uint,
+            naga::ScalarKind::Float => Self::Float,
+            naga::ScalarKind::Bool => Self::Bool,
+        }
+    }
+}
+
+impl From<naga::ImageDimension> for TextureDimension {
+    fn from(dim: naga::ImageDimension) -> Self {
+        match dim {
+            naga::ImageDimension::D1 => Self::D1,
+            naga::ImageDimension::D2 => Self::D2,
+            naga::ImageDimension::D3 => Self::D3,
+            naga::ImageDimension::Cube => Self::Cube,
+        }
+    }
+}
+
+impl From<naga::ShaderStage> for ShaderStage {
+    fn from(stage: naga::ShaderStage) -> Self {
+        match stage {
+            naga::ShaderStage::Vertex => Self::Vertex,
+            naga::ShaderStage::Fragment => Self::Fragment,
+            naga::ShaderStage::Compute => Self::Compute,
+        }
+    }
+}
+
+impl ShaderReflection {
+    pub(crate) fn from_module(module: &Module) -> Self {
+        let entry_points = module
+            .entry_points
+            .iter()
+            .map(|entry_point| {
+                let mut vertex_inputs = Vec::new();
+                if entry_point.stage == naga::ShaderStage::Vertex {
+                    for argument in &entry_point.function.arguments {
+                        collect_vertex_inputs(
+                            module,
+                            argument.name.as_ref(),
+                            argument.ty,
+                            argument.binding.as_ref(),
+                            &mut vertex_inputs,
+                        );
+                    }
+                }
+                EntryPointReflection {
+                    name: entry_point.name.clone(),
+                    stage: entry_point.stage.into(),
+                    vertex_inputs,
+                }
+            })
+            .collect();
+
+        let bindings = module
+            .global_variables
+            .iter()
+            .filter_map(|(_, global)| {
+                let binding = global.binding.as_ref()?;
+                let resource = match global.class {
+                    naga::StorageClass::Uniform => ResourceKind::UniformBuffer,
+                    naga::StorageClass::Storage { access } => ResourceKind::StorageBuffer {
+                        read_only: !access.contains(naga::StorageAccess::STORE),
+                    },
+                    naga::StorageClass::Handle => match module.types[global.ty].inner {
+                        TypeInner::Image {
+                            class: naga::ImageClass::Storage { .. },
+                            ..
+                        } => ResourceKind::StorageTexture,
+                        TypeInner::Image { .. } => ResourceKind::Texture,
+                        TypeInner::Sampler { .. } => ResourceKind::Sampler,
+                        _ => ResourceKind::Other,
+                    },
+                    _ => ResourceKind::Other,
+                };
+                Some(BindingReflection {
+                    group: binding.group,
+                    binding: binding.binding,
+                    name: global.name.clone(),
+                    resource,
+                    ty: shader_type(module, global.ty),
+                })
+            })
+            .collect();
+
+        let overrides = module
+            .constants
+            .iter()
+            .filter_map(|(_, constant)| {
+                let id = constant.specialization?;
+                let kind = match constant.inner {
+                    ConstantInner::Scalar { value, .. } => match value {
+                        ScalarValue::Sint(_) => ScalarKind::Sint,
+                        ScalarValue::Uint(_) => ScalarKind::Uint,
+                        ScalarValue::Float(_) => ScalarKind::Float,
+                        ScalarValue::Bool(_) => ScalarKind::Bool,
+                    },
+                    ConstantInner::Composite { .. } => return None,
+                };
+                Some(OverrideReflection {
+                    id,
+                    name: constant.name.clone(),
+                    kind,
+                })
+            })
+            .collect();
+
+        Self {
+            entry_points,
+            bindings,
+            overrides,
+        }
+    }
+
+    /// Finds the binding at `group`/`binding`, if the shader uses it.
+    pub fn binding(&self, group: u32, binding: u32) -> Option<&BindingReflection> {
+        self.bindings
+            .iter()
+            .find(|b| b.group == group && b.binding == binding)
+    }
+}
+
+fn collect_vertex_inputs(
+    module: &Module,
+    name: Option<&String>,
+    ty: Handle<Type>,
+    binding: Option<&Binding>,
+    inputs: &mut Vec<VertexInputReflection>,
+) {
+    match binding {
+        Some(Binding::Location { location, .. }) => inputs.push(VertexInputReflection {
+            location: *location,
+            name: name.cloned(),
+            ty: shader_type(module, ty),
+        }),
+        Some(Binding::BuiltIn(_)) => (),
+        // structs passed as arguments carry the bindings on their members
+        None => {
+            if let TypeInner::Struct { ref members, .. } = module.types[ty].inner {
+                for member in members {
+                    collect_vertex_inputs(
+                        module,
+                        member.name.as_ref(),
+                        member.ty,
+                        member.binding.as_ref(),
+                        inputs,
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn shader_type(module: &Module, ty: Handle<Type>) -> ShaderType {
+    let types = &module.types;
+    match types[ty].inner {
+        TypeInner::Scalar { kind, width } => ShaderType::Scalar {
+            kind: kind.into(),
+            width,
+        },
+        TypeInner::Vector { size, kind, width } => ShaderType::Vector {
+            size: size as u8,
+            kind: kind.into(),
+            width,
+        },
+        TypeInner::Matrix {
+            columns,
+            rows,
+            width,
+        } => ShaderType::Matrix {
+            columns: columns as u8,
+            rows: rows as u8,
+            width,
+        },
+        TypeInner::Atomic { kind, width } => ShaderType::Atomic {
+            kind: kind.into(),
+            width,
+        },
+        TypeInner::Array { base, size, stride } => ShaderType::Array {
+            base: Box::new(shader_type(module, base)),
+            len: match size {
+                ArraySize::Constant(constant) => match module.constants[constant].inner {
+                    ConstantInner::Scalar {
+                        value: ScalarValue::Uint(len),
+                        ..
+                    } => Some(len as u32),
+                    ConstantInner::Scalar {
+                        value: ScalarValue::Sint(len),
+                        ..
+                    } => Some(len as u32),
+                    _ => None,
+                },
+                ArraySize::Dynamic => None,
+            },
+            stride,
+        },
+        TypeInner::Struct {
+            ref members, span, ..
+        } => ShaderType::Struct {
+            name: types[ty].name.clone(),
+            members: members
+                .iter()
+                .map(|member| StructMemberReflection {
+                    name: member.name.clone(),
+                    offset: member.offset,
+                    ty: shader_type(module, member.ty),
+                })
+                .collect(),
+            size: span,
+        },
+        TypeInner::Image {
+            dim,
+            arrayed,
+            class,
+        } => ShaderType::Texture {
+            dimension: dim.into(),
+            arrayed,
+            class: match class {
+                naga::ImageClass::Sampled { kind, multi } => TextureClass::Sampled {
+                    kind: kind.into(),
+                    multisampled: multi,
+                },
+                naga::ImageClass::Depth { multi } => TextureClass::Depth {
+                    multisampled: multi,
+                },
+                naga::ImageClass::Storage { format, access } => TextureClass::Storage {
+                    format: format.into(),
+                    access: match (
+                        access.contains(naga::StorageAccess::LOAD),
+                        access.contains(naga::StorageAccess::STORE),
+                    ) {
+                        (true, true) => StorageTextureAccess::ReadWrite,
+                        (true, false) => StorageTextureAccess::ReadOnly,
+                        _ => StorageTextureAccess::WriteOnly,
+                    },
+                },
+            },
+        },
+        TypeInner::Sampler { comparison } => ShaderType::Sampler { comparison },
+        ref other => ShaderType::Other(format!("{:?}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXTURES: &str = "
+[[group(0), binding(0)]] var color: texture_2d<f32>;
+[[group(0), binding(1)]] var ids: texture_2d_array<u32>;
+[[group(0), binding(2)]] var depth: texture_depth_2d;
+[[group(0), binding(3)]] var msaa: texture_multisampled_2d<f32>;
+[[group(0), binding(4)]] var output: texture_storage_2d<rgba8unorm, write>;
+[[group(0), binding(5)]] var shadow: sampler_comparison;
+";
+
+    fn reflect(source: &str) -> ShaderReflection {
+        ShaderReflection::from_module(&naga::front::wgsl::parse_str(source).unwrap())
+    }
+
+    #[test]
+    fn texture_classes() {
+        let reflection = reflect(TEXTURES);
+        let binding = |binding| reflection.binding(0, binding).unwrap();
+        assert_eq!(binding(0).ty.to_string(), "texture_2d<f32>");
+        assert_eq!(binding(1).ty.to_string(), "texture_2d_array<u32>");
+        assert_eq!(binding(2).ty.to_string(), "texture_depth_2d");
+        assert_eq!(binding(3).ty.to_string(), "texture_multisampled_2d<f32>");
+        assert_eq!(
+            binding(4).ty.to_string(),
+            "texture_storage_2d<rgba8unorm, write>"
+        );
+        assert_eq!(binding(4).resource, ResourceKind::StorageTexture);
+        assert_eq!(binding(0).resource, ResourceKind::Texture);
+    }
+
+    #[test]
+    fn binding_types() {
+        let reflection = reflect(TEXTURES);
+        let binding_type = |binding| {
+            reflection
+                .binding(0, binding)
+                .unwrap()
+                .binding_type()
+                .unwrap()
+        };
+        assert_eq!(
+            binding_type(1),
+            BindingType::Texture {
+                sample_type: TextureSampleType::Uint,
+                view_dimension: TextureViewDimension::D2Array,
+                multisampled: false,
+            }
+        );
+        assert_eq!(
+            binding_type(2),
+            BindingType::Texture {
+                sample_type: TextureSampleType::Depth,
+                view_dimension: TextureViewDimension::D2,
+                multisampled: false,
+            }
+        );
+        assert_eq!(
+            binding_type(3),
+            BindingType::Texture {
+                sample_type: TextureSampleType::Float { filterable: false },
+                view_dimension: TextureViewDimension::D2,
+                multisampled: true,
+            }
+        );
+        assert_eq!(
+            binding_type(4),
+            BindingType::StorageTexture {
+                access: wgpu::StorageTextureAccess::WriteOnly,
+                format: TextureFormat::Rgba8Unorm,
+                view_dimension: TextureViewDimension::D2,
+            }
+        );
+        assert_eq!(
+            binding_type(5),
+            BindingType::Sampler {
+                filtering: true,
+                comparison: true,
+            }
+        );
+    }
+}
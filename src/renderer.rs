@@ -1,50 +1,553 @@
-use delegate::delegate;
-use std::ops::Range;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    convert::TryFrom,
+    fmt,
+    num::{NonZeroU32, NonZeroU8},
+    ops::Range,
+    panic::Location,
+    sync::{Arc, Mutex},
+    thread::{self, ThreadId},
+    time::Instant,
+};
 
+use raw_window_handle::HasRawWindowHandle;
 use wgpu::*;
+#[cfg(feature = "winit")]
 use winit::{dpi::*, window::*};
 
+use crate::budget::FrameBudget;
+use crate::color::ColorExt;
+use crate::event_bus::{RendererEvent, RendererEventBus};
+use crate::material::{Material, MaterialError, UniformBlock};
+use crate::memory::MemoryTracker;
+use crate::pipeline::{RenderPipelineDescriptorBuilder, RenderPipelineError};
+use crate::reflection::ShaderReflection;
+use crate::shader::{self, ShaderError};
+
 type RenderPipelines = Vec<RenderPipeline>;
+type BindGroups = Vec<BindGroup>;
+
+/// Open validation scopes of each thread, innermost last, with the first error
+/// they caught.
+///
+/// wgpu 0.10 has no error scopes, so they're emulated with the uncaptured
+/// error handler. wgpu-core validates on the calling thread, so errors are
+/// reported on the thread that caused them, while its scope is still open.
+/// Scopes belong to the device like in WebGPU, so renderers sharing a context
+/// on the same thread share them too.
+type ErrorScopes = Arc<Mutex<HashMap<ThreadId, Vec<(String, Option<Error>)>>>>;
+
+struct Shader {
+    module: ShaderModule,
+    source: Cow<'static, str>,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct RenderPipelineId(usize);
 
-pub struct RenderPassBuilder<'a> {
-    render_pass: wgpu::RenderPass<'a>,
-    pipelines: &'a RenderPipelines,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShaderId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BindGroupId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SamplerId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureArrayId {
+    index: usize,
+    layer_count: u32,
+}
+
+impl TextureArrayId {
+    pub fn layer_count(&self) -> u32 {
+        self.layer_count
+    }
+}
+
+/// The polygon mode a pipeline is not currently using, swapped in by
+/// `Renderer::toggle_wireframe`.
+struct PipelineVariant {
+    alternate: Option<RenderPipeline>,
+    wireframe: bool,
+}
+
+struct TextureArray {
+    // kept alive for the view
+    _texture: Texture,
+    view: TextureView,
 }
 
-impl<'a> RenderPassBuilder<'a> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceType {
+    Shader,
+    RenderPipeline,
+    BindGroup,
+    Sampler,
+    TextureArray,
+}
+
+/// Debug information about a resource created by the renderer.
+#[derive(Debug, Clone)]
+pub struct ResourceInfo {
+    pub ty: ResourceType,
+    pub index: usize,
+    /// The label given when creating the resource, or its type and index.
+    pub label: String,
+    /// Where the resource was created, only captured in debug builds.
+    pub created_at: Option<&'static Location<'static>>,
+}
+
+impl fmt::Display for ResourceInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} #{} \"{}\"", self.ty, self.index, self.label)?;
+        if let Some(location) = self.created_at {
+            write!(f, " (created at {})", location)?;
+        }
+        Ok(())
+    }
+}
+
+enum RenderCommand {
+    SetPipeline(RenderPipelineId),
+    SetBindGroup(u32, BindGroupId),
+    Draw(Range<u32>, Range<u32>),
+    PushDebugGroup(String),
+    PopDebugGroup,
+    InsertDebugMarker(String),
+}
+
+/// Records the commands of a render pass.
+///
+/// The commands are encoded once recording is done, so passes without draw
+/// calls can be skipped entirely, see `Renderer::render_pass_conditional`.
+#[derive(Default)]
+pub struct RenderPassBuilder {
+    commands: Vec<RenderCommand>,
+    draw_count: usize,
+}
+
+impl RenderPassBuilder {
     /// Sets the active render pipeline.
     ///
     /// Subsequent draw calls will exhibit the behavior defined by `pipeline`.
     /// # Panics
     /// If pipeline does not exist at runtime
     pub fn set_pipeline(&mut self, id: RenderPipelineId) {
-        let pipeline = self.pipelines.get(id.0).unwrap();
-        self.render_pass.set_pipeline(pipeline);
+        self.commands.push(RenderCommand::SetPipeline(id));
+    }
+    /// Sets the active bind group for a given bind group index.
+    /// # Panics
+    /// If bind group does not exist at runtime
+    pub fn set_bind_group(&mut self, index: u32, id: BindGroupId) {
+        self.commands.push(RenderCommand::SetBindGroup(index, id));
+    }
+    /// Draws primitives from the active vertex buffer(s).
+    pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) {
+        self.commands.push(RenderCommand::Draw(vertices, instances));
+        self.draw_count += 1;
+    }
+    /// Starts a labeled group of commands, shown by graphics debuggers like
+    /// RenderDoc and PIX.
+    ///
+    /// Debug markers are only recorded in debug builds.
+    pub fn push_debug_group(&mut self, label: &str) {
+        if cfg!(debug_assertions) {
+            self.commands
+                .push(RenderCommand::PushDebugGroup(label.to_owned()));
+        }
     }
-    delegate! {
-        to self.render_pass {
-            /// Draws primitives from the active vertex buffer(s).
-            pub fn draw(&mut self, vertices: std::ops::Range<u32>, instances: Range<u32>);
+    /// Ends the group started by the last `push_debug_group`.
+    pub fn pop_debug_group(&mut self) {
+        if cfg!(debug_assertions) {
+            self.commands.push(RenderCommand::PopDebugGroup);
         }
     }
+    /// Marks a single point in the commands, see `push_debug_group`.
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        if cfg!(debug_assertions) {
+            self.commands
+                .push(RenderCommand::InsertDebugMarker(label.to_owned()));
+        }
+    }
+    /// The number of draw calls recorded so far.
+    pub fn draw_count(&self) -> usize {
+        self.draw_count
+    }
+    fn encode<'a>(
+        &self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        pipelines: &'a RenderPipelines,
+        bind_groups: &'a BindGroups,
+    ) {
+        for command in &self.commands {
+            match command {
+                RenderCommand::SetPipeline(id) => {
+                    let pipeline = pipelines
+                        .get(id.0)
+                        .unwrap_or_else(|| panic!("{:?} does not exist", id));
+                    render_pass.set_pipeline(pipeline);
+                }
+                RenderCommand::SetBindGroup(index, id) => {
+                    let bind_group = bind_groups
+                        .get(id.0)
+                        .unwrap_or_else(|| panic!("{:?} does not exist", id));
+                    render_pass.set_bind_group(*index, bind_group, &[]);
+                }
+                RenderCommand::Draw(vertices, instances) => {
+                    render_pass.draw(vertices.clone(), instances.clone());
+                }
+                RenderCommand::PushDebugGroup(label) => render_pass.push_debug_group(label),
+                RenderCommand::PopDebugGroup => render_pass.pop_debug_group(),
+                RenderCommand::InsertDebugMarker(label) => render_pass.insert_debug_marker(label),
+            }
+        }
+    }
+}
+
+const RENDER_PASS_LABEL: &str = "Render Pass";
+
+/// The commands recorded for a single swapchain frame.
+///
+/// Created by `Renderer::begin_frame`, passes are added with
+/// `Renderer::render_pass_into`.
+pub struct FrameEncoder {
+    texture: SurfaceTexture,
+    view: TextureView,
+    encoder: CommandEncoder,
+}
+
+impl FrameEncoder {
+    /// Submits all recorded passes and presents the frame.
+    pub fn submit(self, renderer: &Renderer) {
+        renderer.context.queue.submit(Some(self.encoder.finish()));
+        // dropping the texture presents it
+        drop(self.texture);
+        renderer.event_bus.send(RendererEvent::FrameEnd);
+    }
+}
+
+/// How the swapchain stores colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SwapchainColorSpace {
+    /// Linear shader output is encoded to sRGB when written.
+    #[default]
+    Srgb,
+    /// Shader output is stored as is, shaders have to encode it themselves.
+    Unorm,
+}
+
+/// Re-specifies the format of the views into the swapchain textures.
+///
+/// Some platforms (Android) require the view format to be given explicitly,
+/// e.g. `Rgba8Unorm`, instead of being derived from the swapchain texture.
+///
+/// wgpu 0.10 can't create a view whose format differs from its texture's,
+/// so the swapchain itself is configured with this format, which the surface
+/// has to support. It takes precedence over `RendererConfig::color_space`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurfaceViewFormatOverride {
+    pub format: TextureFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SamplerConfig {
+    /// Used for both magnification and minification.
+    pub filter: FilterMode,
+    pub mip_filter: FilterMode,
+    /// Used in all directions.
+    pub address_mode: AddressMode,
+    /// Maximum anisotropy, valid values are 1, 2, 4, 8 and 16. Above 1,
+    /// `filter` and `mip_filter` have to be `Linear`.
+    pub anisotropy: Option<NonZeroU8>,
+    /// The lowest mip level that is sampled, levels in between are
+    /// fractional.
+    pub lod_min_clamp: f32,
+    /// The highest mip level that is sampled.
+    pub lod_max_clamp: f32,
+}
+
+impl Default for SamplerConfig {
+    fn default() -> Self {
+        Self {
+            filter: FilterMode::Linear,
+            mip_filter: FilterMode::Nearest,
+            address_mode: AddressMode::ClampToEdge,
+            anisotropy: None,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: f32::MAX,
+        }
+    }
+}
+
+impl SamplerConfig {
+    fn validate(&self) -> Result<(), SamplerError> {
+        let anisotropy = match self.anisotropy {
+            Some(anisotropy) => anisotropy.get(),
+            None => return Ok(()),
+        };
+        if anisotropy > 16 || !anisotropy.is_power_of_two() {
+            return Err(SamplerError::InvalidAnisotropy { anisotropy });
+        }
+        if anisotropy > 1
+            && (self.filter, self.mip_filter) != (FilterMode::Linear, FilterMode::Linear)
+        {
+            return Err(SamplerError::AnisotropyWithoutLinearFiltering {
+                anisotropy,
+                filter: self.filter,
+                mip_filter: self.mip_filter,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplerError {
+    /// The anisotropy is not 1, 2, 4, 8 or 16.
+    InvalidAnisotropy { anisotropy: u8 },
+    /// Anisotropic filtering needs linear filtering, including between mip
+    /// levels.
+    AnisotropyWithoutLinearFiltering {
+        anisotropy: u8,
+        filter: FilterMode,
+        mip_filter: FilterMode,
+    },
+}
+
+impl fmt::Display for SamplerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SamplerError::InvalidAnisotropy { anisotropy } => write!(
+                f,
+                "anisotropy must be 1, 2, 4, 8 or 16, but is {}",
+                anisotropy
+            ),
+            SamplerError::AnisotropyWithoutLinearFiltering {
+                anisotropy,
+                filter,
+                mip_filter,
+            } => write!(
+                f,
+                "anisotropy {} requires linear filtering, but the filter is {:?} and the mip filter is {:?}",
+                anisotropy, filter, mip_filter
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SamplerError {}
+
+/// A texture array exceeds the device's limits, see
+/// `Capabilities::check_texture_array`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureArrayError {
+    TooLarge {
+        width: u32,
+        height: u32,
+        max_size: u32,
+    },
+    TooManyLayers {
+        layers: u32,
+        max_layers: u32,
+    },
+}
+
+impl fmt::Display for TextureArrayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TextureArrayError::TooLarge {
+                width,
+                height,
+                max_size,
+            } => write!(
+                f,
+                "texture array layers are {}x{}, but the device only supports up to {}x{}",
+                width, height, max_size, max_size
+            ),
+            TextureArrayError::TooManyLayers { layers, max_layers } => write!(
+                f,
+                "texture array has {} layers, but the device only supports up to {}",
+                layers, max_layers
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TextureArrayError {}
+
+/// Size of a surface in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SurfaceSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[cfg(feature = "winit")]
+impl From<PhysicalSize<u32>> for SurfaceSize {
+    fn from(size: PhysicalSize<u32>) -> Self {
+        Self {
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RendererConfig {
+    /// Defaults to the window's size.
+    pub surface_size: Option<SurfaceSize>,
+    pub color_space: SwapchainColorSpace,
+    /// Defaults to the surface's preferred format. Pipelines target this
+    /// format.
+    pub surface_view_format: Option<SurfaceViewFormatOverride>,
+}
+
+fn swapchain_format_for(format: TextureFormat, color_space: SwapchainColorSpace) -> TextureFormat {
+    use TextureFormat::*;
+    match (format, color_space) {
+        (Bgra8Unorm, SwapchainColorSpace::Srgb) => Bgra8UnormSrgb,
+        (Rgba8Unorm, SwapchainColorSpace::Srgb) => Rgba8UnormSrgb,
+        (Bgra8UnormSrgb, SwapchainColorSpace::Unorm) => Bgra8Unorm,
+        (Rgba8UnormSrgb, SwapchainColorSpace::Unorm) => Rgba8Unorm,
+        (format, _) => format,
+    }
+}
+
+/// What the device supports, captured when it is created.
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    pub features: Features,
+    pub limits: Limits,
+    pub downlevel: DownlevelCapabilities,
+}
+
+impl Capabilities {
+    pub fn anisotropic_filtering(&self) -> bool {
+        self.downlevel
+            .flags
+            .contains(DownlevelFlags::ANISOTROPIC_FILTERING)
+    }
+    /// Whether `Renderer::create_texture_array` can create an array of
+    /// `layers` layers of `width` by `height` on this device.
+    pub fn check_texture_array(
+        &self,
+        width: u32,
+        height: u32,
+        layers: u32,
+    ) -> Result<(), TextureArrayError> {
+        let max_size = self.limits.max_texture_dimension_2d;
+        if width > max_size || height > max_size {
+            return Err(TextureArrayError::TooLarge {
+                width,
+                height,
+                max_size,
+            });
+        }
+        let max_layers = self.limits.max_texture_array_layers;
+        if layers > max_layers {
+            return Err(TextureArrayError::TooManyLayers { layers, max_layers });
+        }
+        Ok(())
+    }
+    /// Logs which path each renderer feature takes on this device.
+    fn log(&self, level: log::Level) {
+        log::log!(level, "Renderer capabilities:");
+        log::log!(
+            level,
+            "  anisotropic filtering: {}",
+            if self.anisotropic_filtering() {
+                "enabled"
+            } else {
+                "ignored by the backend"
+            }
+        );
+        log::log!(
+            level,
+            "  wireframe pipelines: {}",
+            if self.features.contains(Features::NON_FILL_POLYGON_MODE) {
+                "enabled"
+            } else {
+                "unsupported, drawn filled"
+            }
+        );
+        log::log!(
+            level,
+            "  texture arrays: up to {} layers of {}x{}",
+            self.limits.max_texture_array_layers,
+            self.limits.max_texture_dimension_2d,
+            self.limits.max_texture_dimension_2d
+        );
+        log::log!(
+            level,
+            "  bind groups: up to {}",
+            self.limits.max_bind_groups
+        );
+    }
+}
+
+/// The GPU side of a renderer.
+///
+/// Renderers created from the same context share the device and queue, but
+/// each has its own surface, shaders, pipelines and bind groups.
+#[derive(Clone)]
+pub struct RendererContext {
+    instance: Arc<Instance>,
+    adapter: Arc<Adapter>,
+    device: Arc<Device>,
+    queue: Arc<Queue>,
+    error_scopes: ErrorScopes,
+    capabilities: Capabilities,
 }
 
 pub struct Renderer {
-    device: Device,
-    queue: Queue,
+    context: RendererContext,
     surface_and_config: (Surface, SurfaceConfiguration),
     pipelines: RenderPipelines,
+    pipeline_variants: Vec<PipelineVariant>,
+    shaders: Vec<Shader>,
+    bind_groups: BindGroups,
+    samplers: Vec<Sampler>,
+    texture_arrays: Vec<TextureArray>,
+    resources: Vec<ResourceInfo>,
+    event_bus: RendererEventBus,
+    memory: MemoryTracker,
+    /// Keeps the window alive as long as its surface, declared last so it is
+    /// dropped after it.
+    #[cfg(feature = "winit")]
+    window: Option<Arc<Window>>,
 }
 
 impl Renderer {
-    pub fn new(window: &Window, surface_size: Option<PhysicalSize<u32>>) -> Self {
+    /// Creates a renderer for a winit window.
+    ///
+    /// The renderer keeps the window alive, so its surface stays valid.
+    #[cfg(feature = "winit")]
+    pub fn new(window: Arc<Window>, config: RendererConfig) -> Self {
+        // the renderer holds on to the window, so the handle outlives it
+        let mut renderer =
+            unsafe { Self::from_raw_handle(&*window, window.inner_size().into(), config) };
+        renderer.window = Some(window);
+        renderer
+    }
+    /// Creates a renderer for any window that provides a raw window handle,
+    /// e.g. an SDL2 or Qt window.
+    ///
+    /// `window_size` is used unless `config.surface_size` is set.
+    /// # Safety
+    /// The window handle must stay valid for as long as the renderer exists,
+    /// i.e. the window must outlive the renderer.
+    pub unsafe fn from_raw_handle<W: HasRawWindowHandle>(
+        window: &W,
+        window_size: SurfaceSize,
+        config: RendererConfig,
+    ) -> Self {
         // create wgpu instance
         let instance = Instance::new(Backends::all());
         // create surface for window
-        let surface = unsafe { instance.create_surface(window) };
+        let surface = instance.create_surface(window);
         // get gpu handle
         let adapter = pollster::block_on(instance.request_adapter(&RequestAdapterOptions {
             power_preference: PowerPreference::default(),
@@ -55,15 +558,94 @@ impl Renderer {
         let (device, queue) = pollster::block_on(adapter.request_device(
             &DeviceDescriptor {
                 label: None,
-                features: Features::empty(),
+                // only used if the adapter has it, for wireframe pipelines
+                features: adapter.features() & Features::NON_FILL_POLYGON_MODE,
                 limits: Limits::downlevel_defaults().using_resolution(adapter.limits()),
             },
             None,
         ))
         .expect("Failed to create device");
+        let error_scopes = ErrorScopes::default();
+        let handler_scopes = error_scopes.clone();
+        device.on_uncaptured_error(move |error| {
+            let mut scopes = handler_scopes.lock().unwrap();
+            let scope = scopes
+                .get_mut(&thread::current().id())
+                .and_then(|scopes| scopes.last_mut());
+            let error = match (scope, error) {
+                (Some((_, scope_error)), error @ Error::ValidationError { .. }) => {
+                    scope_error.get_or_insert(error);
+                    return;
+                }
+                (_, error) => error,
+            };
+            drop(scopes);
+            // same as wgpu's default handler
+            log::error!("wgpu error: {}\n", error);
+            panic!("Handling wgpu errors as fatal by default");
+        });
+        let capabilities = Capabilities {
+            features: device.features(),
+            limits: device.limits(),
+            downlevel: adapter.get_downlevel_properties(),
+        };
+        capabilities.log(log::Level::Info);
+        let context = RendererContext {
+            capabilities,
+            instance: Arc::new(instance),
+            adapter: Arc::new(adapter),
+            device: Arc::new(device),
+            queue: Arc::new(queue),
+            error_scopes,
+        };
+        let size = config.surface_size.unwrap_or(window_size);
+        Self::with_surface(context, surface, size, config)
+    }
+    /// Creates a renderer for another window on the same GPU, e.g. for
+    /// split-screen.
+    ///
+    /// The renderer keeps the window alive, like `new`.
+    #[cfg(feature = "winit")]
+    pub fn from_context(
+        context: RendererContext,
+        window: Arc<Window>,
+        config: RendererConfig,
+    ) -> Self {
+        // the renderer holds on to the window, so the handle outlives it
+        let mut renderer = unsafe {
+            Self::from_context_and_raw_handle(context, &*window, window.inner_size().into(), config)
+        };
+        renderer.window = Some(window);
+        renderer
+    }
+    /// Like `from_context`, for any window that provides a raw window handle.
+    /// # Safety
+    /// The window handle must stay valid for as long as the renderer exists,
+    /// i.e. the window must outlive the renderer.
+    pub unsafe fn from_context_and_raw_handle<W: HasRawWindowHandle>(
+        context: RendererContext,
+        window: &W,
+        window_size: SurfaceSize,
+        config: RendererConfig,
+    ) -> Self {
+        let surface = context.instance.create_surface(window);
+        let size = config.surface_size.unwrap_or(window_size);
+        Self::with_surface(context, surface, size, config)
+    }
+    fn with_surface(
+        context: RendererContext,
+        surface: Surface,
+        size: SurfaceSize,
+        config: RendererConfig,
+    ) -> Self {
         // configure surface
-        let size = surface_size.unwrap_or(window.inner_size());
-        let swapchain_format = surface.get_preferred_format(&adapter).unwrap();
+        let swapchain_format = match config.surface_view_format {
+            Some(view) => view.format,
+            None => swapchain_format_for(
+                surface.get_preferred_format(&context.adapter).unwrap(),
+                config.color_space,
+            ),
+        };
         let surface_config = SurfaceConfiguration {
             usage: TextureUsages::RENDER_ATTACHMENT,
             format: swapchain_format,
@@ -71,107 +653,911 @@ impl Renderer {
             height: size.height,
             present_mode: PresentMode::Fifo,
         };
-        surface.configure(&device, &surface_config);
+        surface.configure(&context.device, &surface_config);
         Self {
-            device,
-            queue,
+            context,
             surface_and_config: (surface, surface_config),
             pipelines: RenderPipelines::new(),
+            pipeline_variants: Vec::new(),
+            shaders: Vec::new(),
+            bind_groups: BindGroups::new(),
+            samplers: Vec::new(),
+            texture_arrays: Vec::new(),
+            resources: Vec::new(),
+            event_bus: RendererEventBus::default(),
+            memory: MemoryTracker::default(),
+            #[cfg(feature = "winit")]
+            window: None,
         }
     }
-    pub fn set_surface_size(&mut self, surface_size: PhysicalSize<u32>) {
+    pub fn clone_context(&self) -> RendererContext {
+        self.context.clone()
+    }
+    /// Events of this renderer, see `RendererEvent`.
+    pub fn event_bus(&self) -> &RendererEventBus {
+        &self.event_bus
+    }
+    /// GPU memory allocated for the resources of this renderer.
+    ///
+    /// Resources are never freed, so this only grows. That includes material
+    /// buffers, dropping a `Material` is not noticed.
+    pub fn memory_tracker(&self) -> &MemoryTracker {
+        &self.memory
+    }
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.context.capabilities
+    }
+    /// Whether the device has all of `features`, see also `FeatureGated`.
+    pub fn supports_feature(&self, features: Features) -> bool {
+        self.capabilities().features.contains(features)
+    }
+    /// The format render passes to the swapchain render to.
+    ///
+    /// This is the swapchain format, see `SurfaceViewFormatOverride`.
+    pub fn surface_view_format(&self) -> TextureFormat {
+        let (_, config) = &self.surface_and_config;
+        config.format
+    }
+    /// The color space the swapchain ended up in, which can differ from the
+    /// requested one if the preferred format has no counterpart.
+    pub fn swapchain_color_space(&self) -> SwapchainColorSpace {
+        if self.surface_view_format().describe().srgb {
+            SwapchainColorSpace::Srgb
+        } else {
+            SwapchainColorSpace::Unorm
+        }
+    }
+    pub fn set_surface_size(&mut self, surface_size: impl Into<SurfaceSize>) {
+        let surface_size = surface_size.into();
         let (surface, config) = &mut self.surface_and_config;
         config.width = surface_size.width;
         config.height = surface_size.height;
-        surface.configure(&self.device, &config);
+        surface.configure(&self.context.device, config);
+        self.event_bus.send(RendererEvent::Resized {
+            width: surface_size.width,
+            height: surface_size.height,
+        });
     }
-    pub fn load_shader_from_memory(&self, shader: &'static str) -> ShaderModule {
-        self.device.create_shader_module(&ShaderModuleDescriptor {
-            label: Some("Shader"),
-            source: ShaderSource::Wgsl(shader.into()),
+    #[track_caller]
+    fn track_resource(&mut self, ty: ResourceType, index: usize, label: String) {
+        self.resources.push(ResourceInfo {
+            ty,
+            index,
+            label,
+            created_at: if cfg!(debug_assertions) {
+                Some(Location::caller())
+            } else {
+                None
+            },
+        });
+    }
+    /// Looks up the debug information of a resource.
+    pub fn resource_info(&self, ty: ResourceType, index: usize) -> Option<&ResourceInfo> {
+        self.resources
+            .iter()
+            .find(|info| info.ty == ty && info.index == index)
+    }
+    fn describe_resource(&self, ty: ResourceType, index: usize) -> String {
+        match self.resource_info(ty, index) {
+            Some(info) => info.to_string(),
+            None => format!("{:?} #{}", ty, index),
+        }
+    }
+    /// Logs every resource created by the renderer.
+    pub fn dump_resources(&self, level: log::Level) {
+        log::log!(
+            level,
+            "{} resources, {} bytes of GPU memory:",
+            self.resources.len(),
+            self.memory.gpu_allocated_bytes()
+        );
+        for info in &self.resources {
+            log::log!(level, "  {}", info);
+        }
+    }
+    /// Loads a WGSL shader.
+    ///
+    /// Accepts both `include_str!` output and strings built at runtime, e.g.
+    /// file contents; a borrowed `&'static str` is not copied.
+    ///
+    /// `label` shows up in wgpu's error messages and `dump_resources`.
+    #[track_caller]
+    pub fn load_shader_from_memory(
+        &mut self,
+        label: &str,
+        shader: impl Into<Cow<'static, str>>,
+    ) -> ShaderId {
+        let source = shader.into();
+        let module = self
+            .context
+            .device
+            .create_shader_module(&ShaderModuleDescriptor {
+                label: Some(label),
+                source: ShaderSource::Wgsl(source.clone()),
+            });
+        self.shaders.push(Shader { module, source });
+        let index = self.shaders.len() - 1;
+        self.track_resource(ResourceType::Shader, index, label.to_owned());
+        ShaderId(index)
+    }
+    /// Loads a WGSL shader after replacing every `//!use <name>` line with the
+    /// snippet `name` of `shader::lib`, e.g. `//!use color`.
+    ///
+    /// Fails if an included snippet defines a name the shader or another
+    /// snippet defines too, or if the spliced shader doesn't parse.
+    #[track_caller]
+    pub fn load_shader_with_snippets(
+        &mut self,
+        label: &str,
+        source: &str,
+    ) -> Result<ShaderId, ShaderError> {
+        let source = shader::include_snippets(source)?;
+        Ok(self.load_shader_from_memory(label, source))
+    }
+    /// Loads a SPIR-V shader by translating it to WGSL, unlike SPIR-V
+    /// passthrough this works on every backend.
+    ///
+    /// The vertex and fragment entry points are renamed to `vs_main` and
+    /// `fs_main`, unless the shader has several of either.
+    ///
+    /// Logs a warning if the shader uses something WGSL can't express, e.g.
+    /// geometry shaders.
+    #[cfg(feature = "spirv-cross")]
+    #[track_caller]
+    pub fn load_shader_transcoded_from_spirv(
+        &mut self,
+        label: &str,
+        bytes: &[u8],
+    ) -> Result<ShaderId, ShaderError> {
+        let source = shader::spirv_to_wgsl(bytes)?;
+        Ok(self.load_shader_from_memory(label, source))
+    }
+    /// Loads a GLSL shader by translating it to WGSL.
+    ///
+    /// The stage is told from the extension: `.vert`, `.frag` or `.comp`. The
+    /// entry point of vertex and fragment shaders is renamed to `vs_main` and
+    /// `fs_main`. `#version` directives naga doesn't accept, e.g.
+    /// `#version 330 core`, are treated as `#version 450`.
+    #[cfg(feature = "glsl")]
+    #[track_caller]
+    pub fn load_shader_from_glsl_path(
+        &mut self,
+        path: impl AsRef<std::path::Path>,
+    ) -> Result<ShaderId, ShaderError> {
+        let path = path.as_ref();
+        let source = shader::glsl_path_to_wgsl(path)?;
+        Ok(self.load_shader_from_memory(&path.display().to_string(), source))
+    }
+    /// Describes the entry points, bindings, vertex inputs and override
+    /// constants of a loaded shader.
+    /// # Panics
+    /// If shader does not exist at runtime
+    pub fn reflect_shader(&self, id: ShaderId) -> ShaderReflection {
+        let shader = self
+            .shaders
+            .get(id.0)
+            .unwrap_or_else(|| panic!("{:?} does not exist", id));
+        // wgpu already validated the source with the same naga version
+        let module = naga::front::wgsl::parse_str(&shader.source).unwrap_or_else(|error| {
+            panic!(
+                "Failed to reflect {}: {}",
+                self.describe_resource(ResourceType::Shader, id.0),
+                error
+            )
+        });
+        ShaderReflection::from_module(&module)
+    }
+    /// Creates a material for the uniform struct the shader declares at
+    /// `group`/`binding`, with all fields zeroed.
+    #[track_caller]
+    pub fn create_material(
+        &mut self,
+        shader: ShaderId,
+        group: u32,
+        binding: u32,
+    ) -> Result<Material, MaterialError> {
+        let reflection = self.reflect_shader(shader);
+        let block = reflection
+            .binding(group, binding)
+            .and_then(UniformBlock::from_binding)
+            .ok_or_else(|| MaterialError::NoUniformBlock {
+                shader: self.describe_resource(ResourceType::Shader, shader.0),
+                group,
+                binding,
+            })?;
+        let size = block.data.len();
+        self.memory.track_buffer(size as u64);
+        let buffer = self.context.device.create_buffer(&BufferDescriptor {
+            label: Some("Material Buffer"),
+            size: size as BufferAddress,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group_layout =
+            self.context
+                .device
+                .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    label: Some("Material Bind Group Layout"),
+                    entries: &[BindGroupLayoutEntry {
+                        binding,
+                        visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: BufferSize::new(size as u64),
+                        },
+                        count: None,
+                    }],
+                });
+        let label = format!("Material Bind Group #{}", self.bind_groups.len());
+        let bind_group = self.context.device.create_bind_group(&BindGroupDescriptor {
+            label: Some(&label),
+            layout: &bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+        self.bind_groups.push(bind_group);
+        let index = self.bind_groups.len() - 1;
+        self.track_resource(ResourceType::BindGroup, index, label);
+        Ok(Material {
+            block,
+            buffer,
+            bind_group_layout,
+            bind_group: BindGroupId(index),
+            group,
         })
     }
+    /// Uploads all fields set since the last flush in a single write.
+    pub fn flush_material(&self, material: &mut Material) {
+        if let Some(dirty) = material.block.dirty.take() {
+            self.context.queue.write_buffer(
+                &material.buffer,
+                dirty.start as BufferAddress,
+                &material.block.data[dirty],
+            );
+        }
+    }
+    /// Creates a sampler.
+    ///
+    /// Anisotropic filtering is requested even if the adapter doesn't
+    /// support it, which logs a warning, as backends may ignore it. An
+    /// anisotropy wgpu would reject is an error instead.
+    #[track_caller]
+    pub fn create_sampler(&mut self, config: SamplerConfig) -> Result<SamplerId, SamplerError> {
+        config.validate()?;
+        if config.anisotropy.is_some() && !self.capabilities().anisotropic_filtering() {
+            log::warn!("Anisotropic filtering is not supported by the adapter");
+        }
+        let label = format!("Sampler #{}", self.samplers.len());
+        let sampler = self.context.device.create_sampler(&SamplerDescriptor {
+            label: Some(&label),
+            address_mode_u: config.address_mode,
+            address_mode_v: config.address_mode,
+            address_mode_w: config.address_mode,
+            mag_filter: config.filter,
+            min_filter: config.filter,
+            mipmap_filter: config.mip_filter,
+            anisotropy_clamp: config.anisotropy,
+            lod_min_clamp: config.lod_min_clamp,
+            lod_max_clamp: config.lod_max_clamp,
+            ..SamplerDescriptor::default()
+        });
+        self.samplers.push(sampler);
+        let index = self.samplers.len() - 1;
+        self.track_resource(ResourceType::Sampler, index, label);
+        Ok(SamplerId(index))
+    }
+    /// Creates an sRGB 2D texture array, with one layer per slice of tightly
+    /// packed RGBA bytes.
+    ///
+    /// Sampled as `texture_2d_array<f32>` in WGSL.
+    ///
+    /// Fails if the device's limits are exceeded, see
+    /// `Capabilities::check_texture_array`.
+    /// # Panics
+    /// If `width` or `height` is 0, `layers` is empty or a layer is not
+    /// `width * height * 4` bytes
+    #[track_caller]
+    pub fn create_texture_array(
+        &mut self,
+        width: u32,
+        height: u32,
+        layers: &[&[u8]],
+    ) -> Result<TextureArrayId, TextureArrayError> {
+        assert!(
+            width > 0 && height > 0,
+            "Texture array layers must not be empty"
+        );
+        assert!(!layers.is_empty(), "Texture array needs at least one layer");
+        // in usize, large layers overflow u32
+        let layer_size = width as usize * height as usize * 4;
+        for (layer, data) in layers.iter().enumerate() {
+            assert_eq!(
+                data.len(),
+                layer_size,
+                "Texture array layer {} has the wrong size",
+                layer
+            );
+        }
+        self.capabilities()
+            .check_texture_array(width, height, layers.len() as u32)?;
+        let label = format!("Texture Array #{}", self.texture_arrays.len());
+        let texture = self.context.device.create_texture(&TextureDescriptor {
+            label: Some(&label),
+            size: Extent3d {
+                width,
+                height,
+                depth_or_array_layers: layers.len() as u32,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+        });
+        for (layer, data) in layers.iter().enumerate() {
+            self.context.queue.write_texture(
+                ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: Origin3d {
+                        x: 0,
+                        y: 0,
+                        z: layer as u32,
+                    },
+                    aspect: TextureAspect::All,
+                },
+                data,
+                ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: NonZeroU32::new(width * 4),
+                    rows_per_image: NonZeroU32::new(height),
+                },
+                Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+        let view = texture.create_view(&TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::D2Array),
+            ..TextureViewDescriptor::default()
+        });
+        self.memory
+            .track_texture(layer_size as u64 * layers.len() as u64);
+        self.texture_arrays.push(TextureArray {
+            _texture: texture,
+            view,
+        });
+        let index = self.texture_arrays.len() - 1;
+        self.track_resource(ResourceType::TextureArray, index, label);
+        Ok(TextureArrayId {
+            index,
+            layer_count: layers.len() as u32,
+        })
+    }
+    /// Creates a bind group with the texture array at binding 0 and the
+    /// sampler at binding 1, returned with its layout.
+    #[track_caller]
+    pub fn create_texture_array_bind_group(
+        &mut self,
+        texture_array: TextureArrayId,
+        sampler: SamplerId,
+    ) -> (BindGroupLayout, BindGroupId) {
+        let view = &self
+            .texture_arrays
+            .get(texture_array.index)
+            .unwrap_or_else(|| panic!("{:?} does not exist", texture_array))
+            .view;
+        let sampler = self
+            .samplers
+            .get(sampler.0)
+            .unwrap_or_else(|| panic!("{:?} does not exist", sampler));
+        let layout = self
+            .context
+            .device
+            .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("Texture Array Bind Group Layout"),
+                entries: &[
+                    BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Texture {
+                            sample_type: TextureSampleType::Float { filterable: true },
+                            view_dimension: TextureViewDimension::D2Array,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: ShaderStages::FRAGMENT,
+                        ty: BindingType::Sampler {
+                            filtering: true,
+                            comparison: false,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+        let label = format!("Texture Array Bind Group #{}", self.bind_groups.len());
+        let bind_group = self.context.device.create_bind_group(&BindGroupDescriptor {
+            label: Some(&label),
+            layout: &layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+        self.bind_groups.push(bind_group);
+        let index = self.bind_groups.len() - 1;
+        self.track_resource(ResourceType::BindGroup, index, label);
+        (layout, BindGroupId(index))
+    }
     pub fn create_pipeline_layout(
         &self,
         bind_group_layouts: &[&BindGroupLayout],
     ) -> PipelineLayout {
-        self.device
+        self.context
+            .device
             .create_pipeline_layout(&PipelineLayoutDescriptor {
                 label: Some("Pipeline Layout"),
                 bind_group_layouts,
                 push_constant_ranges: &[],
             })
     }
+    #[track_caller]
     pub fn create_render_pipeline(
         &mut self,
         pipeline_layout: &PipelineLayout,
-        shader_module: &ShaderModule,
+        shader: ShaderId,
     ) -> RenderPipelineId {
-        let (_, surface_config) = &self.surface_and_config;
-        let pipeline = self
-            .device
-            .create_render_pipeline(&RenderPipelineDescriptor {
-                label: Some("Pipeline"),
-                layout: Some(&pipeline_layout),
-                vertex: VertexState {
-                    module: &shader_module,
-                    entry_point: "vs_main",
-                    buffers: &[],
-                },
-                fragment: Some(FragmentState {
-                    module: &shader_module,
-                    entry_point: "fs_main",
-                    targets: &[surface_config.format.into()],
-                }),
-                primitive: PrimitiveState {
-                    topology: PrimitiveTopology::TriangleList,
-                    strip_index_format: None,
-                    front_face: FrontFace::Ccw,
-                    cull_mode: Some(Face::Back),
-                    polygon_mode: PolygonMode::Fill,
-                    clamp_depth: false,
-                    conservative: false,
-                },
-                depth_stencil: None,
-                multisample: MultisampleState::default(),
-            });
+        self.build_render_pipeline(RenderPipelineDescriptorBuilder::new(
+            pipeline_layout,
+            shader,
+        ))
+        .expect("Default pipeline descriptor is valid")
+    }
+    #[track_caller]
+    pub fn build_render_pipeline(
+        &mut self,
+        builder: RenderPipelineDescriptorBuilder,
+    ) -> Result<RenderPipelineId, RenderPipelineError> {
+        let primitive = builder.primitive_state()?;
+        let label = builder
+            .label
+            .map(str::to_owned)
+            .unwrap_or_else(|| format!("Pipeline #{}", self.pipelines.len()));
+        let module = |shader: ShaderId| {
+            &self
+                .shaders
+                .get(shader.0)
+                .unwrap_or_else(|| panic!("{:?} does not exist", shader))
+                .module
+        };
+        let vertex_module = module(builder.shader);
+        let fragment_module = module(builder.fragment_shader.unwrap_or(builder.shader));
+        let create = |primitive| {
+            self.context
+                .device
+                .create_render_pipeline(&RenderPipelineDescriptor {
+                    label: Some(&label),
+                    layout: Some(builder.layout),
+                    vertex: VertexState {
+                        module: vertex_module,
+                        entry_point: "vs_main",
+                        buffers: &[],
+                    },
+                    fragment: Some(FragmentState {
+                        module: fragment_module,
+                        entry_point: "fs_main",
+                        targets: &[self.surface_view_format().into()],
+                    }),
+                    primitive,
+                    depth_stencil: None,
+                    multisample: MultisampleState::default(),
+                })
+        };
+        let pipeline = create(primitive);
+        // the layout is only borrowed, so a requested wireframe variant is
+        // compiled now
+        let has_polygons = matches!(
+            primitive.topology,
+            PrimitiveTopology::TriangleList | PrimitiveTopology::TriangleStrip
+        );
+        let alternate = (builder.wireframe_variant
+            && has_polygons
+            && self.supports_feature(Features::NON_FILL_POLYGON_MODE))
+        .then(|| {
+            create(PrimitiveState {
+                polygon_mode: PolygonMode::Line,
+                ..primitive
+            })
+        });
+        self.pipeline_variants.push(PipelineVariant {
+            alternate,
+            wireframe: false,
+        });
         self.pipelines.push(pipeline);
-        RenderPipelineId(self.pipelines.len() - 1)
+        let index = self.pipelines.len() - 1;
+        self.track_resource(ResourceType::RenderPipeline, index, label);
+        Ok(RenderPipelineId(index))
+    }
+    /// Switches the pipeline between drawing filled polygons and only their
+    /// edges.
+    ///
+    /// Does nothing but log a warning if the pipeline wasn't built with
+    /// `RenderPipelineDescriptorBuilder::wireframe_variant`, the backend can't
+    /// draw wireframes, e.g. on WebGL, or the pipeline doesn't draw triangles.
+    /// # Panics
+    /// If the pipeline does not exist at runtime
+    pub fn toggle_wireframe(&mut self, pipeline: RenderPipelineId, enabled: bool) {
+        let description = self.describe_resource(ResourceType::RenderPipeline, pipeline.0);
+        let variant = self
+            .pipeline_variants
+            .get_mut(pipeline.0)
+            .unwrap_or_else(|| panic!("{:?} does not exist", pipeline));
+        if variant.wireframe == enabled {
+            return;
+        }
+        match &mut variant.alternate {
+            Some(alternate) => {
+                std::mem::swap(&mut self.pipelines[pipeline.0], alternate);
+                variant.wireframe = enabled;
+            }
+            None => log::warn!("{} has no wireframe variant", description),
+        }
+    }
+    /// # Panics
+    /// If the pipeline does not exist at runtime
+    pub fn is_wireframe(&self, pipeline: RenderPipelineId) -> bool {
+        self.pipeline_variants
+            .get(pipeline.0)
+            .unwrap_or_else(|| panic!("{:?} does not exist", pipeline))
+            .wireframe
+    }
+    /// Captures validation errors instead of having them panic, until the
+    /// matching `end_validation_scope`. Scopes can be nested.
+    ///
+    /// Only errors caused on the calling thread are captured, each thread has
+    /// its own scopes.
+    pub fn begin_validation_scope(&self, label: &str) {
+        let mut scopes = self.context.error_scopes.lock().unwrap();
+        scopes
+            .entry(thread::current().id())
+            .or_default()
+            .push((label.to_owned(), None));
     }
+    /// Closes the innermost validation scope, logging and returning the first
+    /// validation error that happened inside it.
+    /// # Panics
+    /// If no validation scope is open on the calling thread
+    pub fn end_validation_scope(&self) -> Option<Error> {
+        let mut scopes = self.context.error_scopes.lock().unwrap();
+        let thread_scopes = scopes
+            .get_mut(&thread::current().id())
+            .expect("No validation scope is open");
+        let (label, error) = thread_scopes.pop().expect("No validation scope is open");
+        if thread_scopes.is_empty() {
+            scopes.remove(&thread::current().id());
+        }
+        drop(scopes);
+        if let Some(error) = &error {
+            log::error!("Validation error in {}: {}", label, error);
+        }
+        error
+    }
+    /// Records and submits a render pass to the swapchain.
+    ///
+    /// `clear_color` is linear, it looks the same in either color space.
+    ///
+    /// In debug builds, validation errors of the pass are logged instead of
+    /// panicking.
     pub fn render_pass<F>(&mut self, clear_color: Color, f: F)
     where
         F: FnOnce(&mut RenderPassBuilder),
     {
+        let mut builder = RenderPassBuilder::default();
+        f(&mut builder);
+        self.submit_render_pass(clear_color, &builder);
+    }
+    /// Like `render_pass`, but if `f` records no draw calls, nothing is
+    /// submitted, not even the clear, and the swapchain is not acquired.
+    ///
+    /// Returns whether the pass was submitted.
+    pub fn render_pass_conditional<F>(&mut self, clear_color: Color, f: F) -> bool
+    where
+        F: FnOnce(&mut RenderPassBuilder),
+    {
+        let mut builder = RenderPassBuilder::default();
+        f(&mut builder);
+        if builder.draw_count() == 0 {
+            return false;
+        }
+        self.submit_render_pass(clear_color, &builder);
+        true
+    }
+    /// Like `render_pass`, but skipped if `budget` says the pass `label` would
+    /// take too long, see `FrameBudget::should_skip_pass`.
+    ///
+    /// The CPU time of the pass, including acquiring the swapchain texture, is
+    /// recorded in `budget`. A skipped pass is not skipped the next time.
+    ///
+    /// Returns whether the pass was submitted.
+    pub fn render_pass_with_budget<F>(
+        &mut self,
+        budget: &mut FrameBudget,
+        label: &str,
+        clear_color: Color,
+        f: F,
+    ) -> bool
+    where
+        F: FnOnce(&mut RenderPassBuilder),
+    {
+        if budget.should_skip_pass(label, budget.spent_ns()) {
+            budget.forget(label);
+            return false;
+        }
+        let start = Instant::now();
+        self.render_pass(clear_color, f);
+        let duration_ns = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        budget.record(label, duration_ns);
+        true
+    }
+    fn submit_render_pass(&mut self, clear_color: Color, builder: &RenderPassBuilder) {
+        if cfg!(debug_assertions) {
+            self.begin_validation_scope(RENDER_PASS_LABEL);
+        }
+        let mut frame = self.begin_frame();
+        self.encode_render_pass(&mut frame, LoadOp::Clear(clear_color), builder);
+        frame.submit(self);
+        if cfg!(debug_assertions) {
+            self.end_validation_scope();
+        }
+    }
+    /// Acquires the next swapchain texture and starts recording commands for
+    /// it, see `render_pass_into`.
+    pub fn begin_frame(&self) -> FrameEncoder {
         let (surface, _) = &self.surface_and_config;
-        let frame = surface
+        let texture = surface
             .get_current_frame()
             .expect("Failed to acquire next swapchain texture")
             .output;
-        let view = frame.texture.create_view(&TextureViewDescriptor::default());
+        self.event_bus.send(RendererEvent::FrameBegin {
+            index: self.event_bus.next_frame_index(),
+        });
+        let view = texture
+            .texture
+            .create_view(&TextureViewDescriptor::default());
+        let encoder = self
+            .context
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor { label: None });
+        FrameEncoder {
+            texture,
+            view,
+            encoder,
+        }
+    }
+    /// Records a render pass into `frame`, without submitting it.
+    ///
+    /// `load` decides whether the pass clears the frame, a `LoadOp::Clear`
+    /// color is linear like in `render_pass`.
+    pub fn render_pass_into<F>(&self, frame: &mut FrameEncoder, load: LoadOp<Color>, f: F)
+    where
+        F: FnOnce(&mut RenderPassBuilder),
+    {
+        let mut builder = RenderPassBuilder::default();
+        f(&mut builder);
+        self.encode_render_pass(frame, load, &builder);
+    }
+    fn encode_render_pass(
+        &self,
+        frame: &mut FrameEncoder,
+        load: LoadOp<Color>,
+        builder: &RenderPassBuilder,
+    ) {
+        let load = match (load, self.swapchain_color_space()) {
+            (LoadOp::Clear(color), SwapchainColorSpace::Unorm) => LoadOp::Clear(color.to_srgb()),
+            (load, _) => load,
+        };
+        let mut render_pass = frame.encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some(RENDER_PASS_LABEL),
+            color_attachments: &[RenderPassColorAttachment {
+                view: &frame.view,
+                resolve_target: None,
+                ops: Operations { load, store: true },
+            }],
+            depth_stencil_attachment: None,
+        });
+        builder.encode(&mut render_pass, &self.pipelines, &self.bind_groups);
+    }
+    /// Submits a pass into a 1×1 offscreen texture and waits for it, so drivers
+    /// that compile pipelines lazily do it now instead of on first use.
+    ///
+    /// `f` should set every pipeline that will be used, draw calls are
+    /// optional. Wireframe variants of those pipelines are warmed up too.
+    pub fn warm_up_pipelines<F>(&self, f: F)
+    where
+        F: FnOnce(&mut RenderPassBuilder),
+    {
+        let start = Instant::now();
+        let mut builder = RenderPassBuilder::default();
+        f(&mut builder);
+        let texture = self.context.device.create_texture(&TextureDescriptor {
+            label: Some("Warm-up Target"),
+            size: Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: self.surface_view_format(),
+            usage: TextureUsages::RENDER_ATTACHMENT,
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
         let mut encoder = self
+            .context
             .device
             .create_command_encoder(&CommandEncoderDescriptor { label: None });
         {
-            let render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
-                label: None,
+            let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+                label: Some("Warm-up Pass"),
                 color_attachments: &[RenderPassColorAttachment {
                     view: &view,
                     resolve_target: None,
-                    ops: Operations {
-                        load: LoadOp::Clear(clear_color),
-                        store: true,
-                    },
+                    ops: Operations::default(),
                 }],
                 depth_stencil_attachment: None,
             });
+            builder.encode(&mut render_pass, &self.pipelines, &self.bind_groups);
+            // the variants that aren't active yet, `toggle_wireframe` swaps them in
+            for command in &builder.commands {
+                if let RenderCommand::SetPipeline(id) = command {
+                    let alternate = self
+                        .pipeline_variants
+                        .get(id.0)
+                        .and_then(|variant| variant.alternate.as_ref());
+                    if let Some(alternate) = alternate {
+                        render_pass.set_pipeline(alternate);
+                    }
+                }
+            }
+        }
+        self.context.queue.submit(Some(encoder.finish()));
+        self.context.device.poll(Maintain::Wait);
+        log::info!("Warmed up pipelines in {:?}", start.elapsed());
+    }
+    /// Blocks until the GPU has finished all submitted work, e.g. before
+    /// reading back the results of a `render_pass`.
+    ///
+    /// Only intended for tests and tool scripts, not real-time rendering.
+    pub fn submit_and_wait(&self) {
+        self.context.queue.submit(std::iter::empty());
+        self.context.device.poll(Maintain::Wait);
+    }
+}
 
-            let mut builder = RenderPassBuilder {
-                render_pass,
-                pipelines: &mut self.pipelines,
-            };
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swapchain_formats() {
+        use TextureFormat::*;
+        let format = swapchain_format_for;
+        assert_eq!(
+            format(Bgra8Unorm, SwapchainColorSpace::Srgb),
+            Bgra8UnormSrgb
+        );
+        assert_eq!(
+            format(Rgba8Unorm, SwapchainColorSpace::Srgb),
+            Rgba8UnormSrgb
+        );
+        assert_eq!(
+            format(Bgra8UnormSrgb, SwapchainColorSpace::Unorm),
+            Bgra8Unorm
+        );
+        assert_eq!(
+            format(Rgba8UnormSrgb, SwapchainColorSpace::Unorm),
+            Rgba8Unorm
+        );
+        assert_eq!(
+            format(Bgra8UnormSrgb, SwapchainColorSpace::Srgb),
+            Bgra8UnormSrgb
+        );
+        assert_eq!(format(Rgba8Unorm, SwapchainColorSpace::Unorm), Rgba8Unorm);
+        // formats without a counterpart are kept
+        assert_eq!(format(Rgba16Float, SwapchainColorSpace::Srgb), Rgba16Float);
+    }
 
-            f(&mut builder);
+    // wgpu 0.10's limits for GLES3, WebGL and D3D11
+    fn downlevel() -> Capabilities {
+        Capabilities {
+            features: Features::empty(),
+            limits: Limits::downlevel_defaults(),
+            downlevel: DownlevelCapabilities::default(),
         }
-        self.queue.submit(Some(encoder.finish()));
+    }
+
+    #[test]
+    fn texture_array_limits() {
+        let capabilities = downlevel();
+        assert_eq!(capabilities.check_texture_array(2096, 2096, 256), Ok(()));
+        assert_eq!(
+            capabilities.check_texture_array(4096, 16, 1),
+            Err(TextureArrayError::TooLarge {
+                width: 4096,
+                height: 16,
+                max_size: 2096,
+            })
+        );
+        assert_eq!(
+            capabilities.check_texture_array(16, 16, 257),
+            Err(TextureArrayError::TooManyLayers {
+                layers: 257,
+                max_layers: 256,
+            })
+        );
+    }
+
+    fn anisotropic(anisotropy: u8) -> SamplerConfig {
+        SamplerConfig {
+            mip_filter: FilterMode::Linear,
+            anisotropy: NonZeroU8::new(anisotropy),
+            ..SamplerConfig::default()
+        }
+    }
+
+    #[test]
+    fn sampler_anisotropy() {
+        assert_eq!(SamplerConfig::default().validate(), Ok(()));
+        for anisotropy in [1, 2, 4, 8, 16] {
+            assert_eq!(anisotropic(anisotropy).validate(), Ok(()));
+        }
+        for anisotropy in [3, 6, 32, 255] {
+            assert_eq!(
+                anisotropic(anisotropy).validate(),
+                Err(SamplerError::InvalidAnisotropy { anisotropy })
+            );
+        }
+    }
+
+    #[test]
+    fn sampler_anisotropy_needs_linear_filtering() {
+        let nearest_mips = SamplerConfig {
+            mip_filter: FilterMode::Nearest,
+            ..anisotropic(4)
+        };
+        assert_eq!(
+            nearest_mips.validate(),
+            Err(SamplerError::AnisotropyWithoutLinearFiltering {
+                anisotropy: 4,
+                filter: FilterMode::Linear,
+                mip_filter: FilterMode::Nearest,
+            })
+        );
+        let nearest = SamplerConfig {
+            filter: FilterMode::Nearest,
+            ..anisotropic(16)
+        };
+        assert!(nearest.validate().is_err());
+        // an anisotropy of 1 is the same as none
+        let nearest = SamplerConfig {
+            filter: FilterMode::Nearest,
+            ..anisotropic(1)
+        };
+        assert_eq!(nearest.validate(), Ok(()));
     }
 }
@@ -0,0 +1,493 @@
+use std::{collections::HashMap, error::Error, fmt};
+
+pub mod lib;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShaderError {
+    /// The file could not be read.
+    Read { path: String, message: String },
+    /// The stage of a GLSL file could not be told from its extension, which
+    /// has to be `vert`, `frag` or `comp`.
+    AmbiguousStage { path: String },
+    /// The source could not be parsed.
+    Parse { message: String },
+    /// The source parsed, but is not a valid shader.
+    Validation { message: String },
+    /// The shader could not be written as WGSL.
+    Translation { message: String },
+    /// `//!use <name>` names no snippet of `shader::lib`.
+    UnknownSnippet { name: String },
+    /// `name` is defined by the included `snippets` and by the shader, or by
+    /// several of the snippets.
+    ///
+    /// Functions, types, globals and constants share one namespace.
+    SnippetCollision { name: String, snippets: Vec<String> },
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShaderError::Read { path, message } => {
+                write!(f, "failed to read shader {}: {}", path, message)
+            }
+            ShaderError::AmbiguousStage { path } => write!(
+                f,
+                "can't tell the stage of {}, expected a .vert, .frag or .comp extension",
+                path
+            ),
+            ShaderError::Parse { message } => write!(f, "failed to parse shader: {}", message),
+            ShaderError::Validation { message } => {
+                write!(f, "shader is invalid: {}", message)
+            }
+            ShaderError::Translation { message } => {
+                write!(f, "failed to translate shader to WGSL: {}", message)
+            }
+            ShaderError::UnknownSnippet { name } => {
+                write!(f, "there is no shader snippet called `{}`", name)
+            }
+            ShaderError::SnippetCollision { name, snippets } => match snippets.as_slice() {
+                [snippet] => write!(
+                    f,
+                    "the shader defines `{}`, which the `{}` snippet defines too",
+                    name, snippet
+                ),
+                snippets => write!(
+                    f,
+                    "`{}` is defined by each of the snippets {}",
+                    name,
+                    snippets.join(", ")
+                ),
+            },
+        }
+    }
+}
+
+impl Error for ShaderError {}
+
+/// Replaces every `//!use <name>` line with the snippet `name` of `lib`.
+///
+/// Each snippet is included at most once. Line numbers in errors are off by
+/// the lines the snippets added.
+pub(crate) fn include_snippets(source: &str) -> Result<String, ShaderError> {
+    splice_snippets(source, lib::snippet)
+}
+
+fn splice_snippets<'a>(
+    source: &str,
+    snippet: impl Fn(&str) -> Option<&'a str>,
+) -> Result<String, ShaderError> {
+    let mut included = Vec::new();
+    let mut output = String::with_capacity(source.len());
+    for line in source.lines() {
+        let name = match line.trim().strip_prefix("//!use") {
+            Some(name) => name.trim(),
+            None => {
+                output.push_str(line);
+                output.push('\n');
+                continue;
+            }
+        };
+        if included.iter().any(|&(included, _)| included == name) {
+            continue;
+        }
+        let snippet = snippet(name).ok_or_else(|| ShaderError::UnknownSnippet {
+            name: name.to_owned(),
+        })?;
+        included.push((name, snippet));
+        output.push_str(snippet);
+        output.push('\n');
+    }
+    if !included.is_empty() {
+        check_collisions(&output, &included)?;
+    }
+    Ok(output)
+}
+
+/// Reflects the spliced `source` for names defined more than once, at least
+/// once by one of the `included` snippets.
+///
+/// naga's WGSL frontend accepts redefinitions and silently uses one of them,
+/// so this has to be checked here.
+fn check_collisions(source: &str, included: &[(&str, &str)]) -> Result<(), ShaderError> {
+    let parse = |source| {
+        naga::front::wgsl::parse_str(source).map_err(|error| ShaderError::Parse {
+            message: error.to_string(),
+        })
+    };
+    let module = parse(source)?;
+    let mut snippet_names = Vec::new();
+    for &(name, snippet) in included {
+        let names: Vec<String> = defined_names(&parse(snippet)?)
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        snippet_names.push((name, names));
+    }
+    let mut counts = HashMap::new();
+    let names = defined_names(&module);
+    for &name in &names {
+        *counts.entry(name).or_insert(0) += 1;
+    }
+    for name in names {
+        if counts[name] < 2 {
+            continue;
+        }
+        let snippets: Vec<String> = snippet_names
+            .iter()
+            .filter(|(_, names)| names.iter().any(|defined| defined == name))
+            .map(|(snippet, _)| snippet.to_string())
+            .collect();
+        // shaders defining something twice themselves are left to naga
+        if !snippets.is_empty() {
+            return Err(ShaderError::SnippetCollision {
+                name: name.to_owned(),
+                snippets,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The names of the functions, entry points, types, globals and constants of
+/// `module`, with duplicates.
+fn defined_names(module: &naga::Module) -> Vec<&str> {
+    let functions = module.functions.iter().map(|(_, function)| &function.name);
+    let types = module.types.iter().map(|(_, ty)| &ty.name);
+    let globals = module
+        .global_variables
+        .iter()
+        .map(|(_, global)| &global.name);
+    let constants = module.constants.iter().map(|(_, constant)| &constant.name);
+    functions
+        .chain(types)
+        .chain(globals)
+        .chain(constants)
+        .filter_map(Option::as_deref)
+        .chain(module.entry_points.iter().map(|entry| entry.name.as_str()))
+        .collect()
+}
+
+/// Validates `module` and writes it as WGSL.
+#[cfg(any(feature = "spirv-cross", feature = "glsl"))]
+fn module_to_wgsl(module: &naga::Module) -> Result<String, ShaderError> {
+    use naga::valid::{Capabilities, ValidationFlags, Validator};
+    // only what plain WGSL can express, e.g. no push constants, the device
+    // features are checked by wgpu when the WGSL is loaded
+    let info = Validator::new(ValidationFlags::all(), Capabilities::empty())
+        .validate(module)
+        .map_err(|error| ShaderError::Validation {
+            message: error.to_string(),
+        })?;
+    let wgsl = naga::back::wgsl::write_string(module, &info).map_err(|error| {
+        ShaderError::Translation {
+            message: error.to_string(),
+        }
+    })?;
+    // naga's WGSL output doesn't always parse again, fail here instead of in
+    // wgpu, where it would panic
+    naga::front::wgsl::parse_str(&wgsl).map_err(|error| ShaderError::Translation {
+        message: error.to_string(),
+    })?;
+    Ok(wgsl)
+}
+
+#[cfg(feature = "spirv-cross")]
+pub(crate) fn spirv_to_wgsl(bytes: &[u8]) -> Result<String, ShaderError> {
+    use naga::front::spv::{self, Error};
+    let mut module = spv::parse_u8_slice(bytes, &spv::Options::default()).map_err(|error| {
+        match error {
+            Error::UnsupportedInstruction(..)
+            | Error::UnsupportedCapability(_)
+            | Error::UnsupportedExtension(_)
+            | Error::UnsupportedExtSet(_)
+            | Error::UnsupportedExtInstSet(_)
+            | Error::UnsupportedExtInst(_)
+            | Error::UnsupportedType(_)
+            | Error::UnsupportedExecutionModel(_)
+            | Error::UnsupportedExecutionMode(_)
+            | Error::UnsupportedStorageClass(_)
+            | Error::UnsupportedImageDim(_)
+            | Error::UnsupportedImageFormat(_)
+            | Error::UnsupportedBuiltIn(_)
+            | Error::UnsupportedControlFlow(_)
+            | Error::UnsupportedBinaryOperator(_) => {
+                log::warn!("SPIR-V shader uses a feature WGSL doesn't have: {}", error)
+            }
+            _ => (),
+        }
+        ShaderError::Parse {
+            message: error.to_string(),
+        }
+    })?;
+    rename_entry_points(&mut module);
+    module_to_wgsl(&module)
+}
+
+/// Renames the only vertex and fragment entry points to `vs_main` and
+/// `fs_main`, like `Renderer::build_render_pipeline` expects. Stages with
+/// several entry points keep their names.
+#[cfg(any(feature = "spirv-cross", feature = "glsl"))]
+fn rename_entry_points(module: &mut naga::Module) {
+    use naga::ShaderStage;
+    for (stage, name) in [
+        (ShaderStage::Vertex, "vs_main"),
+        (ShaderStage::Fragment, "fs_main"),
+    ] {
+        let mut entries = module
+            .entry_points
+            .iter_mut()
+            .filter(|entry| entry.stage == stage);
+        if let (Some(entry), None) = (entries.next(), entries.next()) {
+            entry.name = name.to_owned();
+        }
+    }
+}
+
+/// Reads a GLSL file, whose stage is given by its extension, as WGSL.
+///
+/// The entry point is renamed to `vs_main` or `fs_main` for vertex and
+/// fragment shaders, so a pair of files can be combined with
+/// `RenderPipelineDescriptorBuilder::fragment_shader`.
+#[cfg(feature = "glsl")]
+pub(crate) fn glsl_path_to_wgsl(path: &std::path::Path) -> Result<String, ShaderError> {
+    use naga::{front::glsl, ShaderStage};
+    let stage = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("vert") => ShaderStage::Vertex,
+        Some("frag") => ShaderStage::Fragment,
+        Some("comp") => ShaderStage::Compute,
+        _ => {
+            return Err(ShaderError::AmbiguousStage {
+                path: path.display().to_string(),
+            })
+        }
+    };
+    let source = std::fs::read_to_string(path).map_err(|error| ShaderError::Read {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    })?;
+    let mut module = glsl::Parser::default()
+        .parse(&stage.into(), &rewrite_glsl_version(&source))
+        .map_err(|errors| ShaderError::Parse {
+            message: errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+        })?;
+    rename_entry_points(&mut module);
+    module_to_wgsl(&module)
+}
+
+/// naga only accepts `#version 440` and up, so any `#version` line is replaced
+/// by `#version 450`. Line numbers stay the same.
+#[cfg(feature = "glsl")]
+fn rewrite_glsl_version(source: &str) -> String {
+    let mut rewritten = String::with_capacity(source.len());
+    for line in source.lines() {
+        let directive = line.trim_start().strip_prefix('#').map(str::trim_start);
+        match directive {
+            Some(directive) if directive.starts_with("version") => {
+                rewritten.push_str("#version 450")
+            }
+            _ => rewritten.push_str(line),
+        }
+        rewritten.push('\n');
+    }
+    rewritten
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNIPPETS: [&str; 4] = ["color", "noise", "tonemap", "matrix"];
+
+    fn validate(source: &str) {
+        use naga::valid::{Capabilities, ValidationFlags, Validator};
+        let module = naga::front::wgsl::parse_str(source)
+            .unwrap_or_else(|error| panic!("{}\n{}", error, source));
+        Validator::new(ValidationFlags::all(), Capabilities::empty())
+            .validate(&module)
+            .unwrap_or_else(|error| panic!("{}\n{}", error, source));
+    }
+
+    #[test]
+    fn snippets_validate() {
+        for name in SNIPPETS {
+            let source = format!("//!use {}\n[[stage(fragment)]]\nfn fs_main() {{}}\n", name);
+            validate(&include_snippets(&source).unwrap());
+        }
+        let all: String = SNIPPETS
+            .iter()
+            .map(|name| format!("//!use {}\n", name))
+            .collect();
+        validate(&include_snippets(&all).unwrap());
+    }
+
+    #[test]
+    fn sky_validates() {
+        validate(&include_snippets(include_str!("sky.wgsl")).unwrap());
+    }
+
+    #[test]
+    fn unknown_snippet() {
+        assert_eq!(
+            include_snippets("//!use lighting\n"),
+            Err(ShaderError::UnknownSnippet {
+                name: "lighting".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn snippet_collision() {
+        let collision = Err(ShaderError::SnippetCollision {
+            name: "luminance".to_owned(),
+            snippets: vec!["color".to_owned()],
+        });
+        for definition in [
+            "fn luminance(c: vec3<f32>) -> f32 { return c.y; }",
+            "struct luminance { value: f32; };",
+            "var<private> luminance: f32;",
+            "let luminance: f32 = 1.0;",
+            "type luminance = vec3<f32>;",
+            "[[stage(fragment)]] fn luminance() {}",
+        ] {
+            let source = format!("//!use color\n{}\n", definition);
+            assert_eq!(include_snippets(&source), collision, "{}", definition);
+        }
+        // mentions in comments don't count
+        let source = "//!use color\n// fn luminance is provided by color\n";
+        assert!(include_snippets(source).is_ok());
+    }
+
+    #[test]
+    fn snippets_collide_with_each_other() {
+        let snippet = |name: &str| match name {
+            "a" => Some("fn shared() {}\nfn only_a() {}"),
+            "b" => Some("fn shared() {}"),
+            _ => None,
+        };
+        assert_eq!(
+            splice_snippets("//!use a\n//!use b\n", snippet),
+            Err(ShaderError::SnippetCollision {
+                name: "shared".to_owned(),
+                snippets: vec!["a".to_owned(), "b".to_owned()],
+            })
+        );
+        assert!(splice_snippets("//!use a\n//!use a\n", snippet).is_ok());
+    }
+
+    #[test]
+    fn snippets_are_included_once() {
+        let source = include_snippets("//!use color\n  //!use color\nfn a() {}\n").unwrap();
+        assert_eq!(source.matches("fn luminance(").count(), 1);
+        assert_eq!(source, format!("{}\nfn a() {{}}\n", lib::COLOR));
+    }
+
+    // written by naga's SPIR-V backend from WGSL whose entry points are both
+    // called `main`, like glslc output
+    #[cfg(feature = "spirv-cross")]
+    const SPIRV_VERTEX: &[u8] = include_bytes!("shader/fixtures/triangle.vert.spv");
+    #[cfg(feature = "spirv-cross")]
+    const SPIRV_FRAGMENT: &[u8] = include_bytes!("shader/fixtures/triangle.frag.spv");
+
+    #[cfg(feature = "spirv-cross")]
+    #[test]
+    fn spirv_entry_points_are_renamed() {
+        let vertex = spirv_to_wgsl(SPIRV_VERTEX).unwrap();
+        assert!(vertex.contains("fn vs_main("), "{}", vertex);
+        validate(&vertex);
+        let fragment = spirv_to_wgsl(SPIRV_FRAGMENT).unwrap();
+        assert!(fragment.contains("fn fs_main("), "{}", fragment);
+        validate(&fragment);
+    }
+
+    #[cfg(feature = "spirv-cross")]
+    #[test]
+    fn spirv_unsupported_execution_model() {
+        const OP_ENTRY_POINT: u32 = 15;
+        const GEOMETRY: u32 = 3;
+        let mut words: Vec<u32> = SPIRV_VERTEX
+            .chunks(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .collect();
+        // the header is 5 words, every instruction starts with its word count
+        // and opcode
+        let mut index = 5;
+        while words[index] & 0xFFFF != OP_ENTRY_POINT {
+            index += (words[index] >> 16) as usize;
+        }
+        words[index + 1] = GEOMETRY;
+        let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        match spirv_to_wgsl(&bytes) {
+            Err(ShaderError::Parse { message }) => {
+                assert!(message.contains("execution model"), "{}", message)
+            }
+            result => panic!("expected a parse error, got {:?}", result),
+        }
+    }
+
+    #[cfg(feature = "glsl")]
+    const VERTEX: &str = "#version 330 core
+layout(location = 0) out vec2 v_uv;
+void main() {
+    v_uv = vec2(0.5, 0.5);
+    gl_Position = vec4(0.0, 0.0, 0.0, 1.0);
+}
+";
+
+    #[cfg(feature = "glsl")]
+    const FRAGMENT: &str = "#version 330 core
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 color;
+void main() {
+    color = vec4(v_uv, 0.0, 1.0);
+}
+";
+
+    #[cfg(feature = "glsl")]
+    fn glsl_to_wgsl(file_name: &str, source: &str) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "learning-wgpu-{}-{}",
+            std::process::id(),
+            file_name
+        ));
+        std::fs::write(&path, source).unwrap();
+        let wgsl = glsl_path_to_wgsl(&path);
+        std::fs::remove_file(&path).unwrap();
+        wgsl.unwrap()
+    }
+
+    #[cfg(feature = "glsl")]
+    #[test]
+    fn glsl_version_is_rewritten() {
+        assert_eq!(
+            rewrite_glsl_version("#version 330 core\nvoid main() {}"),
+            "#version 450\nvoid main() {}\n"
+        );
+        assert_eq!(
+            rewrite_glsl_version("  #  version 100\n// #version 330\n"),
+            "#version 450\n// #version 330\n"
+        );
+        assert_eq!(rewrite_glsl_version("#define A 1\n"), "#define A 1\n");
+    }
+
+    #[cfg(feature = "glsl")]
+    #[test]
+    fn glsl_pair_produces_a_pipeline() {
+        let vertex = glsl_to_wgsl("pair.vert", VERTEX);
+        let fragment = glsl_to_wgsl("pair.frag", FRAGMENT);
+        assert!(vertex.contains("fn vs_main("), "{}", vertex);
+        assert!(fragment.contains("fn fs_main("), "{}", fragment);
+
+        let device =
+            match crate::test_support::device("shader::tests::glsl_pair_produces_a_pipeline") {
+                Some(device) => device,
+                None => return,
+            };
+        let vertex = crate::test_support::shader(device, "pair.vert", &vertex);
+        let fragment = crate::test_support::shader(device, "pair.frag", &fragment);
+        crate::test_support::render_pipeline(device, &[], &vertex, &fragment);
+    }
+}
@@ -0,0 +1,27 @@
+//! WGSL helpers that shaders can include with `//!use <name>`, see
+//! `Renderer::load_shader_with_snippets`.
+
+/// Bumped whenever a snippet changes in a way that can break shaders using
+/// it.
+pub const VERSION: u32 = 1;
+
+/// `srgb_to_linear`, `linear_to_srgb` and their `_f32` variants, and
+/// `luminance`.
+pub const COLOR: &str = include_str!("lib/color.wgsl");
+/// `hash_u32`, `hash_2d` and `value_noise`.
+pub const NOISE: &str = include_str!("lib/noise.wgsl");
+/// `tonemap_reinhard` and `tonemap_aces`.
+pub const TONEMAP: &str = include_str!("lib/tonemap.wgsl");
+/// `translation`, `scale` and `rotation_z`, as column-major `mat4x4<f32>`.
+pub const MATRIX: &str = include_str!("lib/matrix.wgsl");
+
+/// The snippet called `name` in `//!use <name>`.
+pub fn snippet(name: &str) -> Option<&'static str> {
+    match name {
+        "color" => Some(COLOR),
+        "noise" => Some(NOISE),
+        "tonemap" => Some(TONEMAP),
+        "matrix" => Some(MATRIX),
+        _ => None,
+    }
+}
@@ -0,0 +1,27 @@
+// integer hash by Chris Wellons
+fn hash_u32(x: u32) -> u32 {
+    var h: u32 = x;
+    h = h ^ (h >> 16u);
+    h = h * 2146121005u;
+    h = h ^ (h >> 15u);
+    h = h * 2221713035u;
+    h = h ^ (h >> 16u);
+    return h;
+}
+
+// in [0, 1]
+fn hash_2d(p: vec2<i32>) -> f32 {
+    return f32(hash_u32(u32(p.x) ^ hash_u32(u32(p.y)))) / 4294967295.0;
+}
+
+// smoothly interpolated hash_2d of the surrounding integer coordinates
+fn value_noise(p: vec2<f32>) -> f32 {
+    let cell = vec2<i32>(floor(p));
+    let f = fract(p);
+    let t = f * f * (vec2<f32>(3.0) - 2.0 * f);
+    let a = hash_2d(cell);
+    let b = hash_2d(cell + vec2<i32>(1, 0));
+    let c = hash_2d(cell + vec2<i32>(0, 1));
+    let d = hash_2d(cell + vec2<i32>(1, 1));
+    return mix(mix(a, b, t.x), mix(c, d, t.x), t.y);
+}
@@ -0,0 +1,9 @@
+fn tonemap_reinhard(c: vec3<f32>) -> vec3<f32> {
+    return c / (c + vec3<f32>(1.0));
+}
+
+// Krzysztof Narkowicz's fit of the ACES filmic curve
+fn tonemap_aces(c: vec3<f32>) -> vec3<f32> {
+    let mapped = (c * (2.51 * c + vec3<f32>(0.03))) / (c * (2.43 * c + vec3<f32>(0.59)) + vec3<f32>(0.14));
+    return clamp(mapped, vec3<f32>(0.0), vec3<f32>(1.0));
+}
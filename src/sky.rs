@@ -0,0 +1,92 @@
+use crate::{
+    material::Material,
+    pipeline::RenderPipelineDescriptorBuilder,
+    renderer::{RenderPassBuilder, RenderPipelineId, Renderer, SwapchainColorSpace},
+};
+
+/// A background that fades from `bottom_color` at the bottom of the screen to
+/// `top_color` at the top, without any textures or vertex buffers.
+///
+/// The colors are linear, they are encoded to sRGB by the shader if the
+/// swapchain doesn't do it.
+pub struct GradientSky {
+    pub top_color: [f32; 4],
+    pub bottom_color: [f32; 4],
+    pipeline: RenderPipelineId,
+    material: Material,
+}
+
+impl GradientSky {
+    pub fn new(renderer: &mut Renderer, top_color: [f32; 4], bottom_color: [f32; 4]) -> Self {
+        let shader = renderer
+            .load_shader_with_snippets("Gradient Sky", include_str!("sky.wgsl"))
+            .expect("sky.wgsl only uses snippets of shader::lib");
+        let material = renderer
+            .create_material(shader, 0, 0)
+            .expect("sky.wgsl has a uniform block at group 0, binding 0");
+        let layout = renderer.create_pipeline_layout(&[material.bind_group_layout()]);
+        let pipeline = renderer
+            .build_render_pipeline(
+                RenderPipelineDescriptorBuilder::new(&layout, shader).label("Gradient Sky"),
+            )
+            .expect("Default pipeline descriptor is valid");
+        let mut sky = Self {
+            top_color,
+            bottom_color,
+            pipeline,
+            material,
+        };
+        sky.update(renderer);
+        sky
+    }
+    /// Uploads `top_color` and `bottom_color`, call after changing them.
+    pub fn update(&mut self, renderer: &Renderer) {
+        let encode_srgb = renderer.swapchain_color_space() == SwapchainColorSpace::Unorm;
+        self.material.set("top_color", self.top_color).unwrap();
+        self.material
+            .set("bottom_color", self.bottom_color)
+            .unwrap();
+        self.material
+            .set("encode_srgb", encode_srgb as u32)
+            .unwrap();
+        renderer.flush_material(&mut self.material);
+    }
+    /// Draws the sky, do this first so everything else is drawn on top.
+    pub fn draw(&self, render_pass: &mut RenderPassBuilder) {
+        render_pass.set_pipeline(self.pipeline);
+        render_pass.set_bind_group(self.material.group(), self.material.bind_group());
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wgpu::{BindGroupLayoutDescriptor, BindGroupLayoutEntry, ShaderStages};
+
+    use crate::{reflection::ShaderReflection, shader, test_support};
+
+    #[test]
+    fn pipeline_compiles() {
+        let source = shader::include_snippets(include_str!("sky.wgsl")).unwrap();
+        let module = naga::front::wgsl::parse_str(&source).unwrap();
+        let binding = ShaderReflection::from_module(&module)
+            .binding(0, 0)
+            .and_then(|binding| binding.binding_type())
+            .unwrap();
+        let device = match test_support::device("sky::tests::pipeline_compiles") {
+            Some(device) => device,
+            None => return,
+        };
+        let layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                ty: binding,
+                count: None,
+            }],
+        });
+        let shader = test_support::shader(device, "Gradient Sky", &source);
+        test_support::render_pipeline(device, &[&layout], &shader, &shader);
+    }
+}
@@ -0,0 +1,39 @@
+//!use color
+
+[[block]]
+struct Sky {
+    top_color: vec4<f32>;
+    bottom_color: vec4<f32>;
+    // 1 if the swapchain stores colors as is, without encoding them to sRGB
+    encode_srgb: u32;
+};
+
+[[group(0), binding(0)]]
+var<uniform> sky: Sky;
+
+struct VertexOutput {
+    [[builtin(position)]] clip_position: vec4<f32>;
+    [[location(0)]] ndc_y: f32;
+};
+
+// one triangle covering the whole screen
+[[stage(vertex)]]
+fn vs_main(
+    [[builtin(vertex_index)]] in_vertex_index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+    let x = f32(i32(in_vertex_index & 1u) * 4 - 1);
+    let y = f32(i32(in_vertex_index >> 1u) * 4 - 1);
+    out.clip_position = vec4<f32>(x, y, 0.0, 1.0);
+    out.ndc_y = y;
+    return out;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let color = mix(sky.bottom_color, sky.top_color, in.ndc_y * 0.5 + 0.5);
+    if (sky.encode_srgb != 0u) {
+        return vec4<f32>(linear_to_srgb(color.rgb), color.a);
+    }
+    return color;
+}
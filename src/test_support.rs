@@ -0,0 +1,67 @@
+//! Fixtures for tests that need a GPU.
+
+use std::sync::OnceLock;
+
+use wgpu::*;
+
+/// A headless device shared by every test, created on first use.
+///
+/// `None` if there is no adapter, which is logged with the `test` it skips.
+pub(crate) fn device(test: &str) -> Option<&'static Device> {
+    static DEVICE: OnceLock<Option<Device>> = OnceLock::new();
+    let device = DEVICE
+        .get_or_init(|| {
+            let instance = Instance::new(Backends::all());
+            let adapter =
+                pollster::block_on(instance.request_adapter(&RequestAdapterOptions::default()))?;
+            let (device, _) =
+                pollster::block_on(adapter.request_device(&DeviceDescriptor::default(), None))
+                    .ok()?;
+            Some(device)
+        })
+        .as_ref();
+    if device.is_none() {
+        eprintln!("no adapter, skipping the GPU part of {}", test);
+    }
+    device
+}
+
+/// Compiles WGSL, wgpu panics if it is invalid.
+pub(crate) fn shader(device: &Device, label: &str, source: &str) -> ShaderModule {
+    device.create_shader_module(&ShaderModuleDescriptor {
+        label: Some(label),
+        source: ShaderSource::Wgsl(source.to_owned().into()),
+    })
+}
+
+/// A pipeline drawing into `Rgba8UnormSrgb` with `vs_main` of `vertex` and
+/// `fs_main` of `fragment`, wgpu panics if they don't fit together.
+pub(crate) fn render_pipeline(
+    device: &Device,
+    bind_group_layouts: &[&BindGroupLayout],
+    vertex: &ShaderModule,
+    fragment: &ShaderModule,
+) -> RenderPipeline {
+    let layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts,
+        push_constant_ranges: &[],
+    });
+    device.create_render_pipeline(&RenderPipelineDescriptor {
+        label: Some("Test Pipeline"),
+        layout: Some(&layout),
+        vertex: VertexState {
+            module: vertex,
+            entry_point: "vs_main",
+            buffers: &[],
+        },
+        fragment: Some(FragmentState {
+            module: fragment,
+            entry_point: "fs_main",
+            targets: &[TextureFormat::Rgba8UnormSrgb.into()],
+        }),
+        primitive: PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: MultisampleState::default(),
+    })
+}